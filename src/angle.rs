@@ -0,0 +1,127 @@
+use std::cell::Cell;
+use std::{fmt, str::FromStr};
+
+use crate::error::ComplexError;
+use crate::num::Float;
+
+/// The units that the angle of a polar number can be given in.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum AngleUnit {
+    /// 2π to a turn.
+    #[default]
+    Radians,
+    /// 360 to a turn.
+    Degrees,
+    /// 400 to a turn.
+    Gradians,
+}
+
+thread_local! {
+    static ANGLE_UNIT: Cell<AngleUnit> = const { Cell::new(AngleUnit::Radians) };
+}
+
+/// The unit that angles written without a suffix are read in, and that
+/// `PolarComplex` shows its angle in, on this thread.
+pub fn angle_unit() -> AngleUnit {
+    ANGLE_UNIT.get()
+}
+
+/// Set the unit that angles are read and shown in on this thread.
+pub fn set_angle_unit(unit: AngleUnit) {
+    ANGLE_UNIT.set(unit);
+}
+
+impl AngleUnit {
+    /// The suffix that marks an angle as being in this unit, e.g. `45deg`.
+    pub fn suffix(&self) -> &'static str {
+        match self {
+            AngleUnit::Radians => "rad",
+            AngleUnit::Degrees => "deg",
+            AngleUnit::Gradians => "grad",
+        }
+    }
+
+    /// An angle in this unit converted to radians.
+    pub fn to_radians<T: Float>(&self, angle: T) -> T {
+        match self {
+            AngleUnit::Radians => angle,
+            _ => angle * T::pi() / self.half_turn(),
+        }
+    }
+
+    /// An angle in radians converted to this unit.
+    pub fn from_radians<T: Float>(&self, radians: T) -> T {
+        match self {
+            AngleUnit::Radians => radians,
+            _ => radians * self.half_turn() / T::pi(),
+        }
+    }
+
+    fn half_turn<T: Float>(&self) -> T {
+        match self {
+            AngleUnit::Radians => T::pi(),
+            AngleUnit::Degrees => T::from_f64(180.0),
+            AngleUnit::Gradians => T::from_f64(200.0),
+        }
+    }
+}
+
+impl fmt::Display for AngleUnit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.suffix())
+    }
+}
+
+/// Reads a unit's suffix, or `°` for degrees.
+impl FromStr for AngleUnit {
+    type Err = ComplexError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "rad" => Ok(AngleUnit::Radians),
+            "deg" | "°" => Ok(AngleUnit::Degrees),
+            "grad" => Ok(AngleUnit::Gradians),
+            _ => Err(ComplexError::InvalidArgument(format!("'{s}' is not an angle unit, which is one of rad, deg or grad"))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+    use std::f64::consts::{FRAC_PI_2, PI};
+
+    #[rstest(
+        unit, angle, radians,
+        case::radians(AngleUnit::Radians, 1.5, 1.5),
+        case::degrees(AngleUnit::Degrees, 90.0, FRAC_PI_2),
+        case::negative_degrees(AngleUnit::Degrees, -180.0, -PI),
+        case::gradians(AngleUnit::Gradians, 100.0, FRAC_PI_2),
+        case::gradians_turn(AngleUnit::Gradians, 400.0, 2.0 * PI)
+    )]
+    fn conversion(unit: AngleUnit, angle: f64, radians: f64) {
+        assert_eq!(radians, unit.to_radians(angle));
+        assert!((angle - unit.from_radians(radians)).abs() < 1e-12);
+    }
+
+    #[rstest(
+        s, expected,
+        case::rad("rad", Ok(AngleUnit::Radians)),
+        case::deg("deg", Ok(AngleUnit::Degrees)),
+        case::degree_sign("°", Ok(AngleUnit::Degrees)),
+        case::grad("grad", Ok(AngleUnit::Gradians)),
+        case::unknown("turn", Err(ComplexError::InvalidArgument("'turn' is not an angle unit, which is one of rad, deg or grad".to_owned())))
+    )]
+    fn from_str(s: &str, expected: Result<AngleUnit, ComplexError>) {
+        assert_eq!(expected, s.parse());
+    }
+
+    #[test]
+    fn thread_setting() {
+        assert_eq!(AngleUnit::Radians, angle_unit());
+        set_angle_unit(AngleUnit::Gradians);
+        assert_eq!(AngleUnit::Gradians, angle_unit());
+        set_angle_unit(AngleUnit::Radians);
+    }
+}
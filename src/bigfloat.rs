@@ -0,0 +1,1344 @@
+use std::cell::Cell;
+use std::cmp::Ordering;
+use std::fmt;
+use std::ops::{Add, Div, Mul, Neg, Sub};
+use std::thread::LocalKey;
+
+use crate::error::ComplexError;
+use crate::format::{format_float, format_round_trip};
+use crate::num::{finite_or_err, Float, Num};
+
+const LIMBS: usize = 16;
+// mantissa bits
+const BITS: u32 = 64 * LIMBS as u32;
+// extra bits that the transcendental functions work with
+const GUARD_BITS: u32 = 64;
+// beyond this a result is treated as overflowed or underflowed
+const MAX_EXP: i64 = 1 << 40;
+
+pub const MIN_PRECISION: u32 = 16;
+pub const MAX_PRECISION: u32 = BITS - GUARD_BITS;
+pub const DEFAULT_PRECISION: u32 = 256;
+
+// a mantissa and room below it, for exact intermediate results
+type Wide = [u64; 2 * LIMBS];
+
+thread_local! {
+    static PRECISION: Cell<u32> = const { Cell::new(DEFAULT_PRECISION) };
+    static PI: Cell<Option<(u32, BigFloat)>> = const { Cell::new(None) };
+    static LN_2: Cell<Option<(u32, BigFloat)>> = const { Cell::new(None) };
+    static LN_10: Cell<Option<(u32, BigFloat)>> = const { Cell::new(None) };
+}
+
+/// The number of mantissa bits that `BigFloat` results are rounded to on
+/// this thread.
+pub fn precision() -> u32 {
+    PRECISION.get()
+}
+
+/// Set the number of mantissa bits that `BigFloat` results are rounded to on
+/// this thread, which must be between `MIN_PRECISION` and `MAX_PRECISION`.
+pub fn set_precision(bits: u32) -> Result<(), ComplexError> {
+    if (MIN_PRECISION..=MAX_PRECISION).contains(&bits) {
+        PRECISION.set(bits);
+        Ok(())
+    } else {
+        Err(ComplexError::InvalidArgument(format!(
+            "precision must be between {MIN_PRECISION} and {MAX_PRECISION} bits but was {bits}")))
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Class {
+    Zero,
+    Normal,
+    Infinite,
+    NaN,
+}
+
+/// A software floating point number whose mantissa is rounded to the
+/// thread's working `precision()`, for checking `f64` results to many more
+/// digits. It has signed zeros, infinities and NaN, which behave as the `f64`
+/// ones do, and an exponent range wide enough that only the likes of
+/// `exp(1e20)` overflow.
+///
+/// It's `Copy`, so that `Complex<BigFloat>` works just like `Complex<f64>`,
+/// and so always has room for `MAX_PRECISION` bits.
+#[derive(Clone, Copy)]
+pub struct BigFloat {
+    class: Class,
+    negative: bool,
+    // the value is mant * 2^(exp - BITS), with the top bit of mant set
+    exp: i64,
+    mant: [u64; LIMBS],
+}
+
+impl BigFloat {
+    pub const INFINITY: Self = BigFloat::special(Class::Infinite, false);
+    pub const NEG_INFINITY: Self = BigFloat::special(Class::Infinite, true);
+    pub const NAN: Self = BigFloat::special(Class::NaN, false);
+
+    const fn special(class: Class, negative: bool) -> Self {
+        BigFloat { class, negative, exp: 0, mant: [0; LIMBS] }
+    }
+
+    const fn power_of_two(negative: bool, exp: i64) -> Self {
+        let mut mant = [0; LIMBS];
+        mant[LIMBS - 1] = 1 << 63;
+        BigFloat { class: Class::Normal, negative, exp, mant }
+    }
+
+    pub fn from_u64(n: u64) -> Self {
+        from_u128(false, n as u128, 0)
+    }
+
+    pub fn is_sign_negative(&self) -> bool {
+        self.negative
+    }
+
+    // Multiply by 2^n.
+    fn scale(self, n: i64) -> Self {
+        match self.class {
+            Class::Normal => round(self.negative, self.exp + n, widen(&self.mant)),
+            _ => self,
+        }
+    }
+
+    // Round to the working precision.
+    fn rounded(self) -> Self {
+        self.scale(0)
+    }
+
+    fn trunc(self) -> Self {
+        if self.class != Class::Normal || self.exp >= BITS as i64 {
+            self
+        } else if self.exp <= 0 {
+            BigFloat::special(Class::Zero, self.negative)
+        } else {
+            let mut w = widen(&self.mant);
+            clear_below(&mut w, 2 * BITS - self.exp as u32);
+            round(self.negative, self.exp, w)
+        }
+    }
+
+    fn round_half_up(self) -> Self {
+        (self + BigFloat::power_of_two(false, 0)).floor()
+    }
+
+    // Divide by a small integer, which is much quicker than a full division.
+    fn div_u64(self, d: u64) -> Self {
+        if self.class != Class::Normal {
+            return self;
+        }
+
+        let mut w = widen(&self.mant);
+        let mut rem: u128 = 0;
+        for limb in w.iter_mut().rev() {
+            let n = (rem << 64) | *limb as u128;
+            *limb = (n / d as u128) as u64;
+            rem = n % d as u128;
+        }
+        w[0] |= (rem != 0) as u64;
+
+        round(self.negative, self.exp, w)
+    }
+
+    // Whether adding this to sum would make no difference at the working
+    // precision.
+    fn negligible(&self, sum: &Self) -> bool {
+        self.class == Class::Zero || self.exp < sum.exp - precision() as i64 - 2
+    }
+
+    // The digits of the integer nearest to this, which must be less than
+    // 2^(2 BITS).
+    fn integer_digits(&self) -> String {
+        if self.class != Class::Normal || self.exp <= 0 {
+            return "0".to_owned();
+        }
+
+        let mut w = widen(&self.mant);
+        shr_sticky(&mut w, 2 * BITS - self.exp as u32);
+
+        let mut chunks = Vec::new();
+        while !is_zero(&w) {
+            let mut rem: u128 = 0;
+            for limb in w.iter_mut().rev() {
+                let n = (rem << 64) | *limb as u128;
+                *limb = (n / TEN_19 as u128) as u64;
+                rem = n % TEN_19 as u128;
+            }
+            chunks.push(rem as u64);
+        }
+
+        let mut digits = chunks.pop().map_or("0".to_owned(), |c| c.to_string());
+        for c in chunks.iter().rev() {
+            digits.push_str(&format!("{c:019}"));
+        }
+        digits
+    }
+
+    // The first `count` significant digits and the decimal exponent, so that
+    // the magnitude is about d.ddd * 10^exponent.
+    fn decimal_digits(&self, count: u32) -> (String, i64) {
+        guarded_with(|| {
+            let x = self.abs();
+            let top = self.mant[LIMBS - 1] as f64 / 2f64.powi(64);
+            let mut k = ((self.exp as f64 + top.log2()) * std::f64::consts::LOG10_2).floor() as i64;
+
+            // the estimate of the exponent can be out by one either way
+            for _ in 0..3 {
+                let y = (x * pow10(count as i64 - 1 - k)).round_half_up();
+                if y >= pow10(count as i64) {
+                    k += 1;
+                } else if y < pow10(count as i64 - 1) {
+                    k -= 1;
+                } else {
+                    return (y.integer_digits(), k);
+                }
+            }
+
+            let y = (x * pow10(count as i64 - 1 - k)).round_half_up();
+            (y.integer_digits(), k)
+        })
+    }
+
+    // Fixed point with `places` decimal places, as `{:.places}` gives.
+    fn to_fixed_string(self, places: usize) -> String {
+        let sign = if self.negative { "-" } else { "" };
+        let digits = match self.class {
+            Class::NaN => return "NaN".to_owned(),
+            Class::Infinite => return format!("{sign}inf"),
+            Class::Zero => "0".to_owned(),
+            Class::Normal => {
+                let (_, k) = self.decimal_digits(1);
+                if k + 1 + places as i64 > MAX_DIGITS as i64 {
+                    // too many digits to work out, and all the ones past the
+                    // precision would be zero anyway
+                    let (digits, k) = self.decimal_digits(MAX_DIGITS);
+                    digits + &"0".repeat((k + 1 + places as i64 - MAX_DIGITS as i64) as usize)
+                } else {
+                    guarded_with(|| (self.abs() * pow10(places as i64)).round_half_up()).integer_digits()
+                }
+            }
+        };
+
+        let digits = format!("{digits:0>width$}", width = places + 1);
+        let (whole, fraction) = digits.split_at(digits.len() - places);
+
+        if places == 0 {
+            format!("{sign}{whole}")
+        } else {
+            format!("{sign}{whole}.{fraction}")
+        }
+    }
+
+    // Exponent form with `places` decimal places, as `{:.places e}` gives.
+    fn to_exp_string(self, places: usize) -> String {
+        let sign = if self.negative { "-" } else { "" };
+        let (digits, k) = match self.class {
+            Class::NaN => return "NaN".to_owned(),
+            Class::Infinite => return format!("{sign}inf"),
+            Class::Zero => ("0".repeat(places + 1), 0),
+            Class::Normal => self.decimal_digits(places.min(MAX_DIGITS as usize - 1) as u32 + 1),
+        };
+        let digits = format!("{digits:0<width$}", width = places + 1);
+        let (first, rest) = digits.split_at(1);
+
+        if places == 0 {
+            format!("{sign}{first}e{k}")
+        } else {
+            format!("{sign}{first}.{rest}e{k}")
+        }
+    }
+
+    // Parse a decimal such as `-12.5e-3`.
+    fn parse(s: &str) -> Option<Self> {
+        let (negative, unsigned) = match s.strip_prefix('-') {
+            Some(rest) => (true, rest),
+            None => (false, s.strip_prefix('+').unwrap_or(s)),
+        };
+        let (mantissa, exponent) = match unsigned.split_once(['e', 'E']) {
+            Some((m, e)) => (m, e.parse::<i64>().ok()?),
+            None => (unsigned, 0),
+        };
+        let (whole, fraction) = mantissa.split_once('.').unwrap_or((mantissa, ""));
+        if (whole.is_empty() && fraction.is_empty())
+            || !whole.chars().chain(fraction.chars()).all(|c| c.is_ascii_digit()) {
+            return None;
+        }
+
+        let digits = format!("{whole}{fraction}");
+        let value = guarded(|| {
+            let ten_19 = BigFloat::from_u64(TEN_19);
+            let mut m = BigFloat::ZERO;
+            for chunk in digits.as_bytes().chunks(19) {
+                let n = std::str::from_utf8(chunk).ok()?.parse::<u64>().ok()?;
+                let scale = if chunk.len() == 19 { ten_19 } else { BigFloat::from_u64(10u64.pow(chunk.len() as u32)) };
+                m = m * scale + BigFloat::from_u64(n);
+            }
+            Some(m * pow10(exponent.saturating_sub(fraction.len() as i64)))
+        })?;
+
+        Some(if negative { -value } else { value })
+    }
+}
+
+const TEN_19: u64 = 10_000_000_000_000_000_000;
+// the most significant digits that are worked out, which is plenty for the
+// largest precision
+const MAX_DIGITS: u32 = 400;
+
+// Work at the guarded precision and round the result to the working
+// precision.
+fn guarded(f: impl FnOnce() -> Option<BigFloat>) -> Option<BigFloat> {
+    guarded_with(f).map(BigFloat::rounded)
+}
+
+fn guarded_with<R>(f: impl FnOnce() -> R) -> R {
+    with_precision((precision() + GUARD_BITS).min(BITS), f)
+}
+
+fn with_precision<R>(bits: u32, f: impl FnOnce() -> R) -> R {
+    let p = precision();
+    PRECISION.set(bits);
+    let result = f();
+    PRECISION.set(p);
+    result
+}
+
+fn guarded_value(f: impl FnOnce() -> BigFloat) -> BigFloat {
+    guarded_with(f).rounded()
+}
+
+fn cached(key: &'static LocalKey<Cell<Option<(u32, BigFloat)>>>, f: fn() -> BigFloat) -> BigFloat {
+    let p = precision();
+    match key.get() {
+        Some((q, value)) if q >= p => value.rounded(),
+        _ => {
+            // computed with guard bits, so that the constant is correctly
+            // rounded at this precision
+            let value = guarded_with(f);
+            key.set(Some((p, value)));
+            value.rounded()
+        }
+    }
+}
+
+fn from_u128(negative: bool, n: u128, exp: i64) -> BigFloat {
+    let mut w = [0; 2 * LIMBS];
+    w[0] = n as u64;
+    w[1] = (n >> 64) as u64;
+    round(negative, exp + 2 * BITS as i64, w)
+}
+
+fn widen(mant: &[u64; LIMBS]) -> Wide {
+    let mut w = [0; 2 * LIMBS];
+    w[LIMBS..].copy_from_slice(mant);
+    w
+}
+
+fn is_zero(w: &Wide) -> bool {
+    w.iter().all(|l| *l == 0)
+}
+
+fn leading_zeros(w: &Wide) -> u32 {
+    let mut n = 0;
+    for l in w.iter().rev() {
+        n += l.leading_zeros();
+        if *l != 0 {
+            break;
+        }
+    }
+    n
+}
+
+fn bit(w: &Wide, i: u32) -> bool {
+    w[(i / 64) as usize] >> (i % 64) & 1 == 1
+}
+
+fn any_below(w: &Wide, i: u32) -> bool {
+    let limb = (i / 64) as usize;
+    w[..limb].iter().any(|l| *l != 0) || (!i.is_multiple_of(64) && w[limb] << (64 - i % 64) != 0)
+}
+
+fn clear_below(w: &mut Wide, i: u32) {
+    let limb = (i / 64) as usize;
+    w[..limb].fill(0);
+    if limb < w.len() && !i.is_multiple_of(64) {
+        w[limb] &= !0 << (i % 64);
+    }
+}
+
+fn shl(w: &mut Wide, n: u32) {
+    let (limbs, bits) = ((n / 64) as usize, n % 64);
+    for i in (0..w.len()).rev() {
+        let hi = if i >= limbs { w[i - limbs] } else { 0 };
+        let lo = if i > limbs { w[i - limbs - 1] } else { 0 };
+        w[i] = if bits == 0 { hi } else { hi << bits | lo >> (64 - bits) };
+    }
+}
+
+// Shift right, keeping a record of any bits shifted out in the bottom bit.
+fn shr_sticky(w: &mut Wide, n: u32) {
+    if n >= 2 * BITS {
+        let sticky = !is_zero(w);
+        *w = [0; 2 * LIMBS];
+        w[0] = sticky as u64;
+        return;
+    }
+
+    let sticky = any_below(w, n);
+    let (limbs, bits) = ((n / 64) as usize, n % 64);
+    for i in 0..w.len() {
+        let lo = w.get(i + limbs).copied().unwrap_or(0);
+        let hi = w.get(i + limbs + 1).copied().unwrap_or(0);
+        w[i] = if bits == 0 { lo } else { lo >> bits | hi << (64 - bits) };
+    }
+    w[0] |= sticky as u64;
+}
+
+fn add_wide(a: &mut Wide, b: &Wide) -> bool {
+    let mut carry = false;
+    for (x, y) in a.iter_mut().zip(b) {
+        let (s, c1) = x.overflowing_add(*y);
+        let (s, c2) = s.overflowing_add(carry as u64);
+        *x = s;
+        carry = c1 || c2;
+    }
+    carry
+}
+
+fn sub_wide(a: &mut Wide, b: &Wide) {
+    let mut borrow = false;
+    for (x, y) in a.iter_mut().zip(b) {
+        let (d, b1) = x.overflowing_sub(*y);
+        let (d, b2) = d.overflowing_sub(borrow as u64);
+        *x = d;
+        borrow = b1 || b2;
+    }
+}
+
+fn cmp_wide(a: &Wide, b: &Wide) -> Ordering {
+    a.iter().rev().cmp(b.iter().rev())
+}
+
+// Normalise the value w * 2^(exp - 2 BITS) and round it, half to even, to
+// the working precision.
+fn round(negative: bool, exp: i64, mut w: Wide) -> BigFloat {
+    let zeros = leading_zeros(&w);
+    if zeros == 2 * BITS {
+        return BigFloat::special(Class::Zero, negative);
+    }
+    shl(&mut w, zeros);
+    let mut exp = exp - zeros as i64;
+
+    let cut = 2 * BITS - precision();
+    let half = bit(&w, cut - 1);
+    let lsb = bit(&w, cut);
+    let sticky = any_below(&w, cut - 1);
+    clear_below(&mut w, cut);
+
+    if half && (sticky || lsb) {
+        let mut unit = [0; 2 * LIMBS];
+        unit[(cut / 64) as usize] = 1 << (cut % 64);
+        if add_wide(&mut w, &unit) {
+            w[2 * LIMBS - 1] = 1 << 63;
+            exp += 1;
+        }
+    }
+
+    if exp > MAX_EXP {
+        BigFloat::special(Class::Infinite, negative)
+    } else if exp < -MAX_EXP {
+        BigFloat::special(Class::Zero, negative)
+    } else {
+        let mut mant = [0; LIMBS];
+        mant.copy_from_slice(&w[LIMBS..]);
+        BigFloat { class: Class::Normal, negative, exp, mant }
+    }
+}
+
+fn cmp_magnitude(a: &BigFloat, b: &BigFloat) -> Ordering {
+    a.exp.cmp(&b.exp).then_with(|| a.mant.iter().rev().cmp(b.mant.iter().rev()))
+}
+
+fn add(a: BigFloat, b: BigFloat) -> BigFloat {
+    match (a.class, b.class) {
+        (Class::NaN, _) | (_, Class::NaN) => BigFloat::NAN,
+        (Class::Infinite, Class::Infinite) if a.negative != b.negative => BigFloat::NAN,
+        (Class::Infinite, _) => a,
+        (_, Class::Infinite) => b,
+        (Class::Zero, Class::Zero) => BigFloat::special(Class::Zero, a.negative && b.negative),
+        (Class::Zero, _) => b.rounded(),
+        (_, Class::Zero) => a.rounded(),
+        _ => {
+            let (big, small) = if cmp_magnitude(&a, &b) == Ordering::Less { (b, a) } else { (a, b) };
+
+            // leave room for a carry
+            let exp = big.exp + 1;
+            let mut wb = widen(&big.mant);
+            shr_sticky(&mut wb, 1);
+            let mut ws = widen(&small.mant);
+            shr_sticky(&mut ws, (exp - small.exp).min(2 * BITS as i64) as u32);
+
+            if big.negative == small.negative {
+                add_wide(&mut wb, &ws);
+                round(big.negative, exp, wb)
+            } else {
+                sub_wide(&mut wb, &ws);
+                // x - x is +0
+                let negative = big.negative && !is_zero(&wb);
+                round(negative, exp, wb)
+            }
+        }
+    }
+}
+
+fn mul(a: BigFloat, b: BigFloat) -> BigFloat {
+    let negative = a.negative != b.negative;
+    match (a.class, b.class) {
+        (Class::NaN, _) | (_, Class::NaN) => BigFloat::NAN,
+        (Class::Infinite, Class::Zero) | (Class::Zero, Class::Infinite) => BigFloat::NAN,
+        (Class::Infinite, _) | (_, Class::Infinite) => BigFloat::special(Class::Infinite, negative),
+        (Class::Zero, _) | (_, Class::Zero) => BigFloat::special(Class::Zero, negative),
+        _ => {
+            let mut w = [0; 2 * LIMBS];
+            for (i, x) in a.mant.iter().enumerate() {
+                let mut carry: u128 = 0;
+                for (j, y) in b.mant.iter().enumerate() {
+                    let t = (*x as u128) * (*y as u128) + w[i + j] as u128 + carry;
+                    w[i + j] = t as u64;
+                    carry = t >> 64;
+                }
+                w[i + LIMBS] = carry as u64;
+            }
+            round(negative, a.exp + b.exp, w)
+        }
+    }
+}
+
+fn div(a: BigFloat, b: BigFloat) -> BigFloat {
+    let negative = a.negative != b.negative;
+    match (a.class, b.class) {
+        (Class::NaN, _) | (_, Class::NaN) => BigFloat::NAN,
+        (Class::Infinite, Class::Infinite) | (Class::Zero, Class::Zero) => BigFloat::NAN,
+        (Class::Infinite, _) | (_, Class::Zero) => BigFloat::special(Class::Infinite, negative),
+        (Class::Zero, _) | (_, Class::Infinite) => BigFloat::special(Class::Zero, negative),
+        _ => {
+            // long division, a bit at a time, with the first bit of the
+            // quotient worth 1
+            let mut r = [0; 2 * LIMBS];
+            r[..LIMBS].copy_from_slice(&a.mant);
+            let mut d = [0; 2 * LIMBS];
+            d[..LIMBS].copy_from_slice(&b.mant);
+            let mut q = [0; 2 * LIMBS];
+
+            for i in 0..precision() + 2 {
+                if cmp_wide(&r, &d) != Ordering::Less {
+                    sub_wide(&mut r, &d);
+                    let position = 2 * BITS - 1 - i;
+                    q[(position / 64) as usize] |= 1 << (position % 64);
+                }
+                shl(&mut r, 1);
+            }
+            q[0] |= !is_zero(&r) as u64;
+
+            round(negative, a.exp - b.exp + 1, q)
+        }
+    }
+}
+
+// 10^n
+fn pow10(n: i64) -> BigFloat {
+    let mut result = BigFloat::ONE;
+    let mut base = BigFloat::from_u64(10);
+    let mut e = n.unsigned_abs();
+    while e > 0 {
+        if e & 1 == 1 {
+            result = result * base;
+        }
+        e >>= 1;
+        if e > 0 {
+            base = base * base;
+        }
+    }
+
+    if n < 0 { BigFloat::ONE / result } else { result }
+}
+
+// atanh(t) = t + t^3/3 + t^5/5 + ..., for small t.
+fn atanh_series(t: BigFloat) -> BigFloat {
+    let t2 = t * t;
+    let mut power = t;
+    let mut sum = t;
+    let mut k = 1;
+    loop {
+        power = power * t2;
+        let term = power.div_u64(2 * k + 1);
+        if term.negligible(&sum) {
+            return sum;
+        }
+        sum = sum + term;
+        k += 1;
+    }
+}
+
+// atan(t) = t - t^3/3 + t^5/5 - ..., for small t.
+fn atan_series(t: BigFloat) -> BigFloat {
+    let t2 = t * t;
+    let mut power = t;
+    let mut sum = t;
+    let mut k = 1;
+    loop {
+        power = -(power * t2);
+        let term = power.div_u64(2 * k + 1);
+        if term.negligible(&sum) {
+            return sum;
+        }
+        sum = sum + term;
+        k += 1;
+    }
+}
+
+fn pi() -> BigFloat {
+    cached(&PI, || {
+        // Machin's formula, π = 16 atan(1/5) - 4 atan(1/239)
+        fn atan_inverse(n: u64) -> BigFloat {
+            let mut power = BigFloat::ONE.div_u64(n);
+            let mut sum = power;
+            let mut k = 1;
+            loop {
+                power = -power.div_u64(n * n);
+                let term = power.div_u64(2 * k + 1);
+                if term.negligible(&sum) {
+                    return sum;
+                }
+                sum = sum + term;
+                k += 1;
+            }
+        }
+        atan_inverse(5).scale(4) - atan_inverse(239).scale(2)
+    })
+}
+
+fn ln_2() -> BigFloat {
+    // ln 2 = 2 atanh(1/3)
+    cached(&LN_2, || atanh_series(BigFloat::ONE.div_u64(3)).scale(1))
+}
+
+fn ln_10() -> BigFloat {
+    cached(&LN_10, || ln(BigFloat::from_u64(10)))
+}
+
+fn exp(x: BigFloat) -> BigFloat {
+    match x.class {
+        Class::NaN => return x,
+        Class::Infinite => return if x.negative { BigFloat::ZERO } else { x },
+        Class::Zero => return BigFloat::ONE,
+        Class::Normal if x.exp > 40 => {
+            return if x.negative { BigFloat::ZERO } else { BigFloat::INFINITY };
+        }
+        Class::Normal => {}
+    }
+
+    // x = k ln 2 + r, with |r| <= ln 2 / 2
+    let k = (x / ln_2()).to_f64().round();
+    let r = x - ln_2() * BigFloat::from_f64(k);
+
+    let mut term = BigFloat::ONE;
+    let mut sum = BigFloat::ONE;
+    let mut n = 1;
+    loop {
+        term = (term * r).div_u64(n);
+        if term.negligible(&sum) {
+            return sum.scale(k as i64);
+        }
+        sum = sum + term;
+        n += 1;
+    }
+}
+
+// ln(1 + u), accurate for small u.
+fn ln_1p(u: BigFloat) -> BigFloat {
+    if u.class == Class::Normal && u.exp <= -4 {
+        // ln(1 + u) = 2 atanh(u / (2 + u))
+        atanh_series(u / (u + BigFloat::from_u64(2))).scale(1)
+    } else {
+        ln(u + BigFloat::ONE)
+    }
+}
+
+fn ln(x: BigFloat) -> BigFloat {
+    match x.class {
+        Class::NaN => return x,
+        _ if x.negative && x.class != Class::Zero => return BigFloat::NAN,
+        Class::Zero => return BigFloat::NEG_INFINITY,
+        Class::Infinite => return x,
+        Class::Normal => {}
+    }
+
+    let u = x - BigFloat::ONE;
+    if u.class == Class::Zero {
+        return u;
+    } else if u.exp <= -4 {
+        return ln_1p(u);
+    }
+
+    // x = m 2^e with m in [1/2, 1), and ln m by Newton's method on exp, which
+    // triples the number of correct bits each time
+    let e = x.exp;
+    let m = x.scale(-e);
+    let mut y = BigFloat::from_f64(m.to_f64().ln());
+    let mut bits = 50;
+    while bits < precision() + 8 {
+        let ey = exp(y);
+        y = y + ((m - ey) / (m + ey)).scale(1);
+        bits *= 3;
+    }
+
+    y + ln_2() * BigFloat::from_f64(e as f64)
+}
+
+fn sin_cos(x: BigFloat) -> (BigFloat, BigFloat) {
+    match x.class {
+        Class::NaN | Class::Infinite => return (BigFloat::NAN, BigFloat::NAN),
+        Class::Zero => return (x, BigFloat::ONE),
+        Class::Normal => {}
+    }
+
+    // x = k π/2 + r, with |r| <= π/4. The subtraction cancels the exp(x)
+    // leading bits of k π/2, so π is carried to that many more bits, and a
+    // reduction that cannot fit them has no bits of r left.
+    let bits = precision() as i64 + x.exp.max(0);
+    if bits > BITS as i64 {
+        return (BigFloat::NAN, BigFloat::NAN);
+    }
+    let (r, quadrant) = with_precision(bits as u32, || {
+        let half_pi = pi().scale(-1);
+        let k = (x / half_pi).round_half_up();
+        (x - k * half_pi, (k - (k.scale(-2).floor()).scale(2)).to_f64() as i64)
+    });
+
+    let r2 = r * r;
+    let (mut s_term, mut s) = (r, r);
+    let (mut c_term, mut c) = (BigFloat::ONE, BigFloat::ONE);
+    let mut n = 1;
+    loop {
+        s_term = -(s_term * r2).div_u64(2 * n * (2 * n + 1));
+        c_term = -(c_term * r2).div_u64((2 * n - 1) * (2 * n));
+        if s_term.negligible(&s) && c_term.negligible(&c) {
+            break;
+        }
+        s = s + s_term;
+        c = c + c_term;
+        n += 1;
+    }
+
+    match quadrant {
+        0 => (s, c),
+        1 => (c, -s),
+        2 => (-s, -c),
+        _ => (-c, s),
+    }
+}
+
+// atan(t) for t >= 0.
+fn atan(t: BigFloat) -> BigFloat {
+    if t > BigFloat::ONE {
+        return pi().scale(-1) - atan(BigFloat::ONE / t);
+    }
+
+    // halve the angle three times, atan(t) = 2 atan(t / (1 + sqrt(1 + t²))),
+    // so the series converges quickly
+    let mut t = t;
+    for _ in 0..3 {
+        t = t / (BigFloat::ONE + sqrt(BigFloat::ONE + t * t));
+    }
+
+    atan_series(t).scale(3)
+}
+
+fn atan2(y: BigFloat, x: BigFloat) -> BigFloat {
+    let pi = pi();
+    let angle = match (y.class, x.class) {
+        (Class::NaN, _) | (_, Class::NaN) => return BigFloat::NAN,
+        (Class::Zero, _) => if x.negative { pi } else { BigFloat::ZERO },
+        (Class::Infinite, Class::Infinite) => {
+            if x.negative { pi.scale(-2) * BigFloat::from_u64(3) } else { pi.scale(-2) }
+        }
+        (Class::Infinite, _) | (_, Class::Zero) => pi.scale(-1),
+        (_, Class::Infinite) => if x.negative { pi } else { BigFloat::ZERO },
+        _ => {
+            let a = atan(y.abs() / x.abs());
+            if x.negative { pi - a } else { a }
+        }
+    };
+
+    angle.copysign(y)
+}
+
+fn sqrt(x: BigFloat) -> BigFloat {
+    match x.class {
+        Class::NaN | Class::Zero => return x,
+        _ if x.negative => return BigFloat::NAN,
+        Class::Infinite => return x,
+        Class::Normal => {}
+    }
+
+    // start from the f64 square root, scaling by an even power of two to
+    // keep it in range, and then Newton's method doubles the correct bits
+    let e = x.exp - x.exp.rem_euclid(2);
+    let mut y = BigFloat::from_f64(x.scale(-e).to_f64().sqrt()).scale(e / 2);
+    let mut bits = 50;
+    while bits < precision() + 8 {
+        y = (y + x / y).scale(-1);
+        bits *= 2;
+    }
+    y
+}
+
+fn cbrt(x: BigFloat) -> BigFloat {
+    if x.class != Class::Normal {
+        return x;
+    }
+
+    let a = x.abs();
+    let e = a.exp - a.exp.rem_euclid(3);
+    let mut y = BigFloat::from_f64(a.scale(-e).to_f64().cbrt()).scale(e / 3);
+    let mut bits = 50;
+    while bits < precision() + 8 {
+        y = (y.scale(1) + a / (y * y)).div_u64(3);
+        bits *= 2;
+    }
+    y.copysign(x)
+}
+
+fn powf(x: BigFloat, n: BigFloat) -> BigFloat {
+    if n.class == Class::Zero || x == BigFloat::ONE {
+        return BigFloat::ONE;
+    } else if x.class == Class::NaN || n.class == Class::NaN {
+        return BigFloat::NAN;
+    }
+
+    let integer = n.trunc() == n;
+    let odd = integer && n.scale(-1).trunc() != n.scale(-1);
+
+    let magnitude = match x.class {
+        Class::Zero => if n.negative { BigFloat::INFINITY } else { BigFloat::ZERO },
+        _ if x.negative && !integer => return BigFloat::NAN,
+        _ => exp(n * ln(x.abs())),
+    };
+
+    if x.negative && odd { -magnitude } else { magnitude }
+}
+
+fn sinh(x: BigFloat) -> BigFloat {
+    if x.class != Class::Normal {
+        return x;
+    } else if x.exp > 0 {
+        let e = exp(x);
+        return (e - BigFloat::ONE / e).scale(-1);
+    }
+
+    // the series avoids the cancellation of the exponentials for small x
+    let x2 = x * x;
+    let mut term = x;
+    let mut sum = x;
+    let mut n = 1;
+    loop {
+        term = (term * x2).div_u64(2 * n * (2 * n + 1));
+        if term.negligible(&sum) {
+            return sum;
+        }
+        sum = sum + term;
+        n += 1;
+    }
+}
+
+fn cosh(x: BigFloat) -> BigFloat {
+    match x.class {
+        Class::NaN => x,
+        Class::Infinite => x.abs(),
+        _ => {
+            let e = exp(x);
+            (e + BigFloat::ONE / e).scale(-1)
+        }
+    }
+}
+
+fn asinh(x: BigFloat) -> BigFloat {
+    if x.class != Class::Normal {
+        return x;
+    }
+
+    let a = x.abs();
+    let r = if a.exp > BITS as i64 {
+        // a² would lose a, and asinh(a) = ln(2a) to well within precision
+        ln(a) + ln_2()
+    } else {
+        // asinh(a) = ln(1 + a + a² / (1 + sqrt(1 + a²))), accurate for small a
+        let a2 = a * a;
+        ln_1p(a + a2 / (BigFloat::ONE + sqrt(BigFloat::ONE + a2)))
+    };
+
+    r.copysign(x)
+}
+
+impl PartialEq for BigFloat {
+    fn eq(&self, other: &Self) -> bool {
+        self.partial_cmp(other) == Some(Ordering::Equal)
+    }
+}
+
+impl PartialOrd for BigFloat {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        let sign = |x: &Self| match x.class {
+            Class::Zero => 0,
+            _ if x.negative => -1,
+            _ => 1,
+        };
+
+        match (self.class, other.class) {
+            (Class::NaN, _) | (_, Class::NaN) => None,
+            _ if sign(self) != sign(other) => Some(sign(self).cmp(&sign(other))),
+            (Class::Zero, _) => Some(Ordering::Equal),
+            (Class::Infinite, Class::Infinite) => Some(Ordering::Equal),
+            _ => {
+                let magnitude = match (self.class, other.class) {
+                    (Class::Infinite, _) => Ordering::Greater,
+                    (_, Class::Infinite) => Ordering::Less,
+                    _ => cmp_magnitude(self, other),
+                };
+                Some(if self.negative { magnitude.reverse() } else { magnitude })
+            }
+        }
+    }
+}
+
+impl Add for BigFloat {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        add(self, other)
+    }
+}
+
+impl Sub for BigFloat {
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self {
+        add(self, -other)
+    }
+}
+
+impl Mul for BigFloat {
+    type Output = Self;
+
+    fn mul(self, other: Self) -> Self {
+        mul(self, other)
+    }
+}
+
+impl Div for BigFloat {
+    type Output = Self;
+
+    fn div(self, other: Self) -> Self {
+        div(self, other)
+    }
+}
+
+impl Neg for BigFloat {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        BigFloat { negative: !self.negative, ..self }
+    }
+}
+
+/// Without a precision, all the digits the working precision justifies.
+impl fmt::Display for BigFloat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match f.precision() {
+            Some(places) => f.write_str(&self.to_fixed_string(places)),
+            None => f.write_str(&self.to_exp_string(significant_digits())),
+        }
+    }
+}
+
+impl fmt::LowerExp for BigFloat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let places = f.precision().unwrap_or_else(significant_digits);
+        f.write_str(&self.to_exp_string(places))
+    }
+}
+
+impl fmt::Debug for BigFloat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "BigFloat({:e})", self)
+    }
+}
+
+// The decimal places that the working precision is good for.
+fn significant_digits() -> usize {
+    (precision() as f64 * std::f64::consts::LOG10_2) as usize - 1
+}
+
+impl Num for BigFloat {
+    const ZERO: Self = BigFloat::special(Class::Zero, false);
+    const ONE: Self = BigFloat::power_of_two(false, 1);
+    const MINUS_ONE: Self = BigFloat::power_of_two(true, 1);
+
+    fn checked_add(self, other: Self) -> Result<Self, ComplexError> {
+        finite_or_err(self + other)
+    }
+
+    fn checked_sub(self, other: Self) -> Result<Self, ComplexError> {
+        finite_or_err(self - other)
+    }
+
+    fn checked_mul(self, other: Self) -> Result<Self, ComplexError> {
+        finite_or_err(self * other)
+    }
+
+    fn from_decimal_str(s: &str) -> Option<Self> {
+        BigFloat::parse(s)
+    }
+
+    fn to_std_string(&self, magnitude: usize, precision: usize) -> String {
+        format_float(*self, magnitude, precision)
+    }
+
+    fn to_literal_string(&self) -> String {
+        format_round_trip(*self)
+    }
+}
+
+impl Float for BigFloat {
+    fn from_f64(f: f64) -> Self {
+        if f.is_nan() {
+            return BigFloat::NAN;
+        } else if f.is_infinite() {
+            return BigFloat::special(Class::Infinite, f < 0.0);
+        } else if f == 0.0 {
+            return BigFloat::special(Class::Zero, f.is_sign_negative());
+        }
+
+        let bits = f.to_bits();
+        let biased = ((bits >> 52) & 0x7ff) as i64;
+        let fraction = bits & ((1 << 52) - 1);
+        let (mantissa, exp) = if biased == 0 {
+            (fraction, -1074)
+        } else {
+            (fraction | (1 << 52), biased - 1075)
+        };
+
+        from_u128(f < 0.0, mantissa as u128, exp)
+    }
+
+    fn to_f64(self) -> f64 {
+        let magnitude = match self.class {
+            Class::NaN => return f64::NAN,
+            Class::Zero => 0.0,
+            Class::Infinite => f64::INFINITY,
+            Class::Normal => {
+                // scale in steps, so the intermediate values stay in range
+                let mut f = self.mant[LIMBS - 1] as f64;
+                let mut e = self.exp - 64;
+                while e.abs() > 1000 {
+                    let step = 1000 * e.signum();
+                    f *= 2f64.powi(step as i32);
+                    e -= step;
+                }
+                f * 2f64.powi(e as i32)
+            }
+        };
+
+        if self.negative { -magnitude } else { magnitude }
+    }
+
+    fn pi() -> Self {
+        guarded_value(pi)
+    }
+
+    fn ln_10() -> Self {
+        guarded_value(ln_10)
+    }
+
+    fn max_value() -> Self {
+        // just short of 2^MAX_EXP
+        BigFloat::ONE.scale(MAX_EXP - 1) * (BigFloat::from_u64(2) - BigFloat::epsilon())
+    }
+
+    fn min_positive() -> Self {
+        BigFloat::power_of_two(false, -MAX_EXP)
+    }
+
+    fn epsilon() -> Self {
+        BigFloat::power_of_two(false, 2 - precision() as i64)
+    }
+
+    fn infinity() -> Self {
+        BigFloat::INFINITY
+    }
+
+    fn checked_div(self, other: Self) -> Result<Self, ComplexError> {
+        finite_or_err(self / other)
+    }
+
+    fn is_finite(self) -> bool {
+        matches!(self.class, Class::Zero | Class::Normal)
+    }
+
+    fn is_nan(self) -> bool {
+        self.class == Class::NaN
+    }
+
+    fn is_infinite(self) -> bool {
+        self.class == Class::Infinite
+    }
+
+    fn abs(self) -> Self {
+        BigFloat { negative: false, ..self }
+    }
+
+    fn max(self, other: Self) -> Self {
+        // as for f64, NaN only if both are
+        if self.is_nan() || other > self { other } else { self }
+    }
+
+    fn floor(self) -> Self {
+        let t = self.trunc();
+        if self.negative && t != self { t - BigFloat::ONE } else { t }
+    }
+
+    fn fract(self) -> Self {
+        self - self.trunc()
+    }
+
+    fn rem_euclid(self, other: Self) -> Self {
+        let r = self - other * (self / other).trunc();
+        if r < BigFloat::ZERO { r + other.abs() } else { r }
+    }
+
+    fn copysign(self, sign: Self) -> Self {
+        BigFloat { negative: sign.negative, ..self }
+    }
+
+    fn hypot(self, other: Self) -> Self {
+        if self.is_infinite() || other.is_infinite() {
+            BigFloat::INFINITY
+        } else {
+            guarded_value(|| sqrt(self * self + other * other))
+        }
+    }
+
+    fn sqrt(self) -> Self {
+        guarded_value(|| sqrt(self))
+    }
+
+    fn cbrt(self) -> Self {
+        guarded_value(|| cbrt(self))
+    }
+
+    fn powf(self, n: Self) -> Self {
+        guarded_value(|| powf(self, n))
+    }
+
+    fn exp(self) -> Self {
+        guarded_value(|| exp(self))
+    }
+
+    fn ln(self) -> Self {
+        guarded_value(|| ln(self))
+    }
+
+    fn log10(self) -> Self {
+        let l = guarded_value(|| ln(self) / ln_10());
+
+        // make exact powers of ten come out exactly, so their floor is right
+        let k = l.round_half_up();
+        if l.is_finite() && (l - k).abs() < BigFloat::power_of_two(false, -(precision() as i64) / 2)
+            && pow10(k.to_f64() as i64) == self {
+            k
+        } else {
+            l
+        }
+    }
+
+    fn sin(self) -> Self {
+        guarded_value(|| sin_cos(self).0)
+    }
+
+    fn cos(self) -> Self {
+        guarded_value(|| sin_cos(self).1)
+    }
+
+    fn sin_cos(self) -> (Self, Self) {
+        let (s, c) = guarded_with(|| sin_cos(self));
+        (s.rounded(), c.rounded())
+    }
+
+    fn tan(self) -> Self {
+        guarded_value(|| {
+            let (s, c) = sin_cos(self);
+            s / c
+        })
+    }
+
+    fn atan2(self, other: Self) -> Self {
+        guarded_value(|| atan2(self, other))
+    }
+
+    fn sinh(self) -> Self {
+        guarded_value(|| sinh(self))
+    }
+
+    fn cosh(self) -> Self {
+        guarded_value(|| cosh(self))
+    }
+
+    fn asinh(self) -> Self {
+        guarded_value(|| asinh(self))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::complex::Complex;
+    use rstest::rstest;
+
+    fn big(s: &str) -> BigFloat {
+        BigFloat::from_decimal_str(s).unwrap()
+    }
+
+    // Whether a and b agree to within 2^-bits relative to b.
+    fn close(a: BigFloat, b: BigFloat, bits: i64) -> bool {
+        (a - b).abs() <= b.abs() * BigFloat::power_of_two(false, 1 - bits)
+    }
+
+    #[test]
+    fn precision_limits() {
+        assert_eq!(Ok(()), set_precision(100));
+        assert_eq!(100, precision());
+        assert!(set_precision(MAX_PRECISION + 1).is_err());
+        assert!(set_precision(1).is_err());
+        assert_eq!(100, precision());
+        set_precision(DEFAULT_PRECISION).unwrap();
+    }
+
+    #[rstest(
+        f,
+        case::one(1.0),
+        case::fraction(-0.1),
+        case::big(1.5e300),
+        case::subnormal(5e-324),
+        case::negative_zero(-0.0)
+    )]
+    fn f64_round_trip(f: f64) {
+        let b = BigFloat::from_f64(f);
+        assert_eq!(f.to_bits(), b.to_f64().to_bits());
+    }
+
+    #[rstest(
+        a, b, sum, product,
+        case::small("1.5", "2.25", "3.75", "3.375"),
+        case::signs("-7", "3", "-4", "-21"),
+        case::cancel("0.1", "-0.1", "0", "-0.01")
+    )]
+    fn arithmetic(a: &str, b: &str, sum: &str, product: &str) {
+        assert_eq!(big(sum), big(a) + big(b));
+        assert_eq!(big(product), big(a) * big(b));
+        assert_eq!(big(a), big(product) / big(b));
+    }
+
+    #[test]
+    fn third() {
+        let third = BigFloat::ONE / BigFloat::from_u64(3);
+        assert_eq!("0.33333333333333333333333333333333333333333333333333", format!("{third:.50}"));
+        assert!(close(third * BigFloat::from_u64(3), BigFloat::ONE, 255));
+    }
+
+    #[rstest(
+        value, expected,
+        case::pi(BigFloat::pi(), "3.14159265358979323846264338327950288419716939937510582097494"),
+        case::e(BigFloat::ONE.exp(), "2.71828182845904523536028747135266249775724709369995957496697"),
+        case::ln_2(BigFloat::from_u64(2).ln(), "0.69314718055994530941723212145817656807550013436025525412068"),
+        case::ln_10(BigFloat::ln_10(), "2.30258509299404568401799145468436420760110148862877297603333"),
+        case::sqrt_2(BigFloat::from_u64(2).sqrt(), "1.41421356237309504880168872420969807856967187537694807317668"),
+        case::cbrt_minus_2(BigFloat::from_f64(-2.0).cbrt(), "-1.25992104989487316476721060727822835057025146470150798008198"),
+        case::sin_1(BigFloat::ONE.sin(), "0.84147098480789650665250232163029899962256306079837106567275"),
+        case::cos_100(BigFloat::from_u64(100).cos(), "0.86231887228768393410193851395084253551008400853551082928016"),
+        case::tanh_30(Complex::new(big("30"), BigFloat::ZERO).tanh().unwrap().re, "0.99999999999999999999999998248697847460695932302253455187463"),
+        case::sin_huge(big("1e100").sin(), "-0.37237612366127668826208669555316429571966788356743470236442"),
+        case::cos_huge(big("1e100").cos(), "-0.92808190507465534345619464377695592818318207643905039332511"),
+        case::atan2(BigFloat::ONE.atan2(BigFloat::from_u64(2)), "0.46364760900080611621425623146121440202853705428612026381093"),
+        case::asinh_small(big("1e-30").asinh(), "0.00000000000000000000000000000100000000000000000000000000000"),
+        case::sinh_half(big("0.5").sinh(), "0.52109530549374736162242562641149155910592898261148052794609"),
+        case::powf(BigFloat::from_u64(2).powf(big("0.5")), "1.41421356237309504880168872420969807856967187537694807317668")
+    )]
+    fn functions(value: BigFloat, expected: &str) {
+        assert_eq!(expected, format!("{value:.59}"));
+    }
+
+    #[test]
+    fn reduction_out_of_bits() {
+        assert!(big("1e300").sin().is_nan());
+    }
+
+    #[rstest(
+        y, x, expected,
+        case::positive_zero(0.0, 1.0, 0.0),
+        case::negative_zero(-0.0, 1.0, -0.0),
+        case::left_of_cut(0.0, -1.0, std::f64::consts::PI),
+        case::right_of_cut(-0.0, -1.0, -std::f64::consts::PI),
+        case::up(1.0, 0.0, std::f64::consts::FRAC_PI_2),
+        case::third_quadrant(-1.0, -1.0, -3.0 * std::f64::consts::FRAC_PI_4)
+    )]
+    fn atan2_signs(y: f64, x: f64, expected: f64) {
+        let result = BigFloat::from_f64(y).atan2(BigFloat::from_f64(x)).to_f64();
+        assert_eq!(expected.to_bits(), result.to_bits());
+    }
+
+    #[rstest(
+        s, magnitude, places, expected,
+        case::simple("123.45", 6, 3, "123.45"),
+        case::big("123456789.123456", 6, 3, "1.235e8"),
+        case::tiny("0.000000123456", 6, 3, "1.235e-7"),
+        case::power_of_ten("1e6", 6, 3, "1e6"),
+        case::beyond_f64("-2.5e400", 6, 3, "-2.5e400"),
+        case::zero("0", 6, 3, "0")
+    )]
+    fn std_string(s: &str, magnitude: usize, places: usize, expected: &str) {
+        assert_eq!(expected, big(s).to_std_string(magnitude, places));
+    }
+
+    #[rstest(
+        s,
+        case::empty(""),
+        case::dot("."),
+        case::letters("1x"),
+        case::bad_exponent("1e")
+    )]
+    fn parse_invalid(s: &str) {
+        assert_eq!(None, BigFloat::from_decimal_str(s));
+    }
+
+    #[test]
+    fn overflow() {
+        assert_eq!(Err(ComplexError::Overflow), big("1e200000000000").checked_mul(big("1e200000000000")));
+        assert_eq!(Err(ComplexError::NaNProduced), BigFloat::INFINITY.checked_sub(BigFloat::INFINITY));
+        assert!(BigFloat::from_u64(1 << 50).exp().is_infinite());
+    }
+
+    #[test]
+    fn complex() {
+        let z = Complex::new(BigFloat::ONE, BigFloat::ONE);
+        let w = Complex::new(BigFloat::from_u64(3), -BigFloat::ONE);
+        assert_eq!("{0.2 + 0.4i}", (z / w).to_std_string(12, 60));
+
+        // i^i = e^(-π/2)
+        let ii = Complex::<BigFloat>::I.powc(Complex::I).unwrap();
+        assert_eq!("{0.207879576350761908546955619834978770033877841631769608075136}", ii.to_std_string(12, 60));
+    }
+}
@@ -1,172 +1,707 @@
-use std::{f64::consts::PI, fmt, option::Option};
-
-use crate::format::format_f64;
+use std::{fmt, ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Sub, SubAssign}};
 
+use crate::angle::{angle_unit, AngleUnit};
+use crate::error::ComplexError;
+use crate::num::{finite_or_err, Float, Num};
 
+/// A complex number with parts of type `T`, which is `f64` unless otherwise
+/// given. Addition, subtraction and multiplication work for any `Num`, so
+/// integer parts are fine, but division and the transcendental functions need
+/// `T` to be a `Float`. Where the part type can't be inferred, such as in
+/// `Complex64::ZERO.ln()`, the aliases `Complex64` and `Complex32` name it.
 #[derive(Copy, Clone, Debug, PartialEq)]
-pub struct Complex {
+pub struct Complex<T = f64> {
     // real part
-    pub re: f64,
+    pub re: T,
     // imaginary part
-    pub im: f64
+    pub im: T
+}
+
+pub type Complex32 = Complex<f32>;
+pub type Complex64 = Complex<f64>;
+
+/// How floating point arithmetic treats results that aren't finite.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum ArithmeticMode {
+    /// Any infinite or NaN result is an error, as is dividing by zero.
+    #[default]
+    Strict,
+    /// Infinities, signed zeros and NaNs are values like any other, and follow
+    /// the rules of IEEE 754 and C99 Annex G, so `1 / 0` is infinite.
+    Ieee,
 }
 
+/// A complex number in polar form, `r e^(iθ)`. The angle is in radians and
+/// isn't kept in any particular range, so `normalised` gives the principal one.
 #[derive(Copy, Clone, Debug, PartialEq)]
-pub struct PolarComplex {
+pub struct PolarComplex<T = f64> {
     // modulus
-    r: f64,
+    r: T,
     // angle (in radians)
-    theta: f64
+    theta: T
 }
 
-impl PolarComplex {
-    pub const fn new(r: f64, theta: f64) -> Self {
+impl<T: Float> PolarComplex<T> {
+    pub const fn new(r: T, theta: T) -> Self {
         PolarComplex { r, theta }
     }
 
-    pub fn to_complex(&self) -> Complex {
-        let re = self.r * f64::cos(self.theta);
-        let im = self.r * f64::sin(self.theta);
+    /// With the angle in degrees, of which there are 360 in a turn.
+    pub fn from_degrees(r: T, degrees: T) -> Self {
+        PolarComplex::new(r, AngleUnit::Degrees.to_radians(degrees))
+    }
+
+    /// With the angle in gradians, of which there are 400 in a turn.
+    pub fn from_gradians(r: T, gradians: T) -> Self {
+        PolarComplex::new(r, AngleUnit::Gradians.to_radians(gradians))
+    }
+
+    /// The modulus.
+    pub fn r(&self) -> T {
+        self.r
+    }
+
+    /// The angle in radians.
+    pub fn theta(&self) -> T {
+        self.theta
+    }
+
+    /// The angle in degrees.
+    pub fn theta_degrees(&self) -> T {
+        AngleUnit::Degrees.from_radians(self.theta)
+    }
+
+    /// The angle in gradians.
+    pub fn theta_gradians(&self) -> T {
+        AngleUnit::Gradians.from_radians(self.theta)
+    }
+
+    /// The same number with a non-negative modulus and the principal angle,
+    /// in `(-π, π]`, matching `Complex::to_polar`.
+    pub fn normalised(&self) -> Self {
+        let (r, theta) = self.non_negative();
+        let theta = theta.rem_euclid(two::<T>() * T::pi());
+
+        if theta > T::pi() {
+            PolarComplex::new(r, theta - two::<T>() * T::pi())
+        } else {
+            PolarComplex::new(r, theta)
+        }
+    }
 
-        Complex::new(re, im)
+    /// The same number with a non-negative modulus and the angle in `[0, 2π)`.
+    pub fn normalised_positive(&self) -> Self {
+        let (r, theta) = self.non_negative();
+        PolarComplex::new(r, theta.rem_euclid(two::<T>() * T::pi()))
     }
 
+    // A negative modulus is a positive one half a turn round.
+    fn non_negative(&self) -> (T, T) {
+        if self.r < T::ZERO {
+            (-self.r, self.theta + T::pi())
+        } else {
+            (self.r, self.theta)
+        }
+    }
+
+    /// Raise to a real power, `rᵖ e^(ipθ)`. As the angle is taken as it is,
+    /// this is the principal value only if the angle is normalised.
+    pub fn pow(&self, pow: T) -> Self {
+        PolarComplex::new(self.r.powf(pow), self.theta * pow)
+    }
+
+    pub fn to_complex(&self) -> Complex<T> {
+        // in half turns, so that a right angle such as 90° lands exactly on an
+        // axis
+        let (sin, cos) = sin_cos_pi(self.theta / T::pi());
+
+        Complex::new(self.r * cos, self.r * sin)
+    }
+
+    /// As `@{r, theta}`, with the angle in the unit set by
+    /// `angle::set_angle_unit` and suffixed unless that is radians.
     pub fn to_std_string(&self, magnitude: usize, precision: usize) -> String {
-        format!("@{{{}, {}}}", format_f64(self.r, magnitude, precision), format_f64(self.theta, magnitude, precision))
+        let unit = angle_unit();
+        let theta = unit.from_radians(self.theta).to_std_string(magnitude, precision);
+
+        match unit {
+            AngleUnit::Radians => format!("@{{{}, {}}}", self.r.to_std_string(magnitude, precision), theta),
+            _ => format!("@{{{}, {}{}}}", self.r.to_std_string(magnitude, precision), theta, unit.suffix()),
+        }
+    }
+}
+
+/// Multiplies the moduli and adds the angles.
+impl<T: Float> Mul for PolarComplex<T> {
+    type Output = Self;
+
+    fn mul(self, other: Self) -> Self {
+        PolarComplex::new(self.r * other.r, self.theta + other.theta)
+    }
+}
+
+/// Divides the moduli and subtracts the angles.
+impl<T: Float> Div for PolarComplex<T> {
+    type Output = Self;
+
+    fn div(self, other: Self) -> Self {
+        PolarComplex::new(self.r / other.r, self.theta - other.theta)
+    }
+}
+
+impl<T: Float> From<Complex<T>> for PolarComplex<T> {
+    fn from(c: Complex<T>) -> Self {
+        c.to_polar()
+    }
+}
+
+impl<T: Float> From<PolarComplex<T>> for Complex<T> {
+    fn from(p: PolarComplex<T>) -> Self {
+        p.to_complex()
     }
 }
 
-impl Complex {
-    pub const fn new(re: f64, im: f64) -> Self {
+impl<T: Num> Complex<T> {
+    pub const fn new(re: T, im: T) -> Self {
         Complex { re, im } 
     }
 
-    pub const ZERO: Self = Self::new(0.0, 0.0);
-    pub const ONE: Self = Self::new(1.0, 0.0);
-    pub const I: Self = Self::new(0.0, 1.0);
-    pub const MINUS_I: Self = Self::new(0.0, -1.0);
+    pub const ZERO: Self = Self::new(T::ZERO, T::ZERO);
+    pub const ONE: Self = Self::new(T::ONE, T::ZERO);
+    pub const I: Self = Self::new(T::ZERO, T::ONE);
+    pub const MINUS_I: Self = Self::new(T::ZERO, T::MINUS_ONE);
 
     pub fn to_std_string(&self, magnitude: usize, precision: usize) -> String {
-        if self.im == 0.0 {
-            format!("{{{}}}", format_f64(self.re, magnitude, precision))
-        } else if self.re == 0.0 {
-            format!("{{{}}}", im_to_string(self.im, magnitude, precision))
-        } else if self.im < 0.0 {
-            format!("{{{} - {}}}", format_f64(self.re, magnitude, precision), im_to_string(-self.im, magnitude, precision))
+        self.format_parts(|x| x.to_std_string(magnitude, precision))
+    }
+
+    /// Format as a literal that parses back to exactly this number.
+    pub fn to_literal_string(&self) -> String {
+        self.format_parts(|x| x.to_literal_string())
+    }
+
+    fn format_parts(&self, part: impl Fn(T) -> String) -> String {
+        if self.im == T::ZERO {
+            format!("{{{}}}", part(self.re))
+        } else if self.re == T::ZERO {
+            format!("{{{}}}", im_to_string(part(self.im)))
+        } else if self.im < T::ZERO {
+            format!("{{{} - {}}}", part(self.re), im_to_string(part(-self.im)))
+        } else {
+            format!("{{{} + {}}}", part(self.re), im_to_string(part(self.im)))
+        }
+    }
+
+    /// Add, returning an error if either part of the result can't be
+    /// represented.
+    pub fn checked_add(&self, other: Self) -> Result<Self, ComplexError> {
+        Ok(Complex::new(
+            self.re.checked_add(other.re)?,
+            self.im.checked_add(other.im)?))
+    }
+
+    /// Subtract, returning an error if either part of the result can't be
+    /// represented.
+    pub fn checked_sub(&self, other: Self) -> Result<Self, ComplexError> {
+        Ok(Complex::new(
+            self.re.checked_sub(other.re)?,
+            self.im.checked_sub(other.im)?))
+    }
+
+    /// Multiply, returning an error if either part of the result, or any of
+    /// the products making it up, can't be represented.
+    pub fn checked_mul(&self, other: Self) -> Result<Self, ComplexError> {
+        let re = self.re.checked_mul(other.re)?.checked_sub(self.im.checked_mul(other.im)?)?;
+        let im = self.re.checked_mul(other.im)?.checked_add(other.re.checked_mul(self.im)?)?;
+
+        Ok(Complex::new(re, im))
+    }
+
+    /// The complex conjugate, `re - im i`.
+    pub fn conj(&self) -> Self {
+        Complex::new(self.re, -self.im)
+    }
+
+    /// The norm `re² + im²`, i.e. the square of the modulus, returning an
+    /// error if it can't be represented.
+    pub fn checked_norm(&self) -> Result<T, ComplexError> {
+        self.re.checked_mul(self.re)?.checked_add(self.im.checked_mul(self.im)?)
+    }
+
+    /// Raise to a whole number power by repeated squaring, returning an
+    /// error if the result can't be represented.
+    pub fn checked_pow(&self, n: u32) -> Result<Self, ComplexError> {
+        let mut result = Self::ONE;
+        let mut base = *self;
+        let mut n = n;
+
+        while n > 0 {
+            if n & 1 == 1 {
+                result = result.checked_mul(base)?;
+            }
+            n >>= 1;
+            if n > 0 {
+                base = base.checked_mul(base)?;
+            }
+        }
+
+        Ok(result)
+    }
+}
+
+impl<T: Float> Complex<T> {
+    /// The modulus `|z|`, computed without overflow where it's representable.
+    pub fn abs(&self) -> T {
+        self.re.hypot(self.im)
+    }
+
+    /// The principal argument, in `(-π, π]`.
+    pub fn arg(&self) -> T {
+        self.im.atan2(self.re)
+    }
+
+    /// The unit number in the same direction, `z / |z|`, or zero for zero.
+    pub fn sign(&self) -> Self {
+        if *self == Self::ZERO {
+            *self
         } else {
-            format!("{{{} + {}}}", format_f64(self.re, magnitude, precision), im_to_string(self.im, magnitude, precision))
+            *self / self.abs()
         }
     }
 
-    pub fn to_polar(&self) -> PolarComplex {
-        let r = f64::sqrt(self.re * self.re + self.im * self.im);
-        let theta = f64::atan2(self.im, self.re);
+    pub fn to_polar(&self) -> PolarComplex<T> {
+        let r = self.re.hypot(self.im);
+        let theta = self.im.atan2(self.re);
         PolarComplex::new(r, theta)
     }
 
-    pub fn add(&self, other: Complex) -> Option<Complex> {
-        Some(Complex::new(
-            checked_add(self.re, other.re)?, 
-            checked_add(self.im, other.im)?))
+    /// Raise to a whole number power by repeated squaring, which is exact
+    /// wherever the products are, so `(1 + i)⁸` is exactly 16. A negative
+    /// power squares the reciprocal, and zero to a negative power is a
+    /// division by zero.
+    pub fn powi(&self, n: i32) -> Result<Self, ComplexError> {
+        if n < 0 {
+            Self::ONE.checked_div(*self)?.checked_pow(n.unsigned_abs())
+        } else {
+            self.checked_pow(n.unsigned_abs())
+        }
     }
 
-    pub fn sub(&self, other: Complex) -> Option<Complex> {
-        Some(Complex::new(
-            checked_sub(self.re, other.re)?,
-            checked_sub(self.im, other.im)?))
+    /// The principal value of `zᵖ`, which is `|z|ᵖ e^(ipθ)` for `θ = arg(z)`
+    /// in `(-π, π]`, so as with `ln` the branch cut lies along the negative
+    /// real axis and the sign of a zero imaginary part picks the side. Whole
+    /// number powers that fit an `i32` go by way of `powi` instead. `0⁰` is 1,
+    /// zero to a positive power is zero, and zero to a negative power is a
+    /// division by zero.
+    pub fn pow(&self, pow: T) -> Result<Self, ComplexError> {
+        if let Some(n) = as_i32(pow) {
+            return self.powi(n);
+        }
+
+        if *self == Self::ZERO {
+            return if pow > T::ZERO {
+                Ok(Self::ZERO)
+            } else {
+                Err(ComplexError::DivisionByZero)
+            };
+        }
+
+        let polar = self.to_polar();
+        let r = finite_or_err(polar.r.powf(pow))?;
+        // in half turns, so that results landing on an axis are exact
+        let (sin, cos) = sin_cos_pi(polar.theta / T::pi() * pow);
+
+        finite_complex_or_err(r * cos, r * sin)
     }
 
-    pub fn mul(&self, other: Complex) -> Option<Complex> {
-        let re = self.re * other.re - self.im * other.im;
-        let im = self.re * other.im + other.re * self.im;
-        
-        finite_complex_or_none(re, im)
+    /// The principal value of `zʷ`, which is `e^(w ln z)` with the principal
+    /// logarithm, so the branch cut is that of `ln`. A real power goes by way
+    /// of `pow`, so whole numbers are exact. Zero to a power with a positive
+    /// real part is zero, to a power with a negative real part is a division
+    /// by zero, and to a non-zero imaginary power is undefined.
+    pub fn powc(&self, pow: Self) -> Result<Self, ComplexError> {
+        if pow.im == T::ZERO {
+            return self.pow(pow.re);
+        }
+
+        if *self == Self::ZERO {
+            return if pow.re > T::ZERO {
+                Ok(Self::ZERO)
+            } else if pow.re < T::ZERO {
+                Err(ComplexError::DivisionByZero)
+            } else {
+                Err(ComplexError::InvalidArgument(format!("0 to the power {pow} is undefined")))
+            };
+        }
+
+        self.ln()?.checked_mul(pow)?.exp()
+    }
+
+    /// `powc` with no checks on the result, so that zero to a power with a
+    /// negative real part is infinite.
+    pub fn ieee_powc(&self, pow: Self) -> Self {
+        if pow.im == T::ZERO {
+            return self.ieee_pow(pow.re);
+        }
+
+        if *self == Self::ZERO {
+            let nan = T::ZERO * T::infinity();
+            return if pow.re > T::ZERO {
+                Self::ZERO
+            } else if pow.re < T::ZERO {
+                Complex::new(T::infinity(), nan)
+            } else {
+                Complex::new(nan, nan)
+            };
+        }
+
+        self.ieee_ln().ieee_mul(pow).ieee_exp()
     }
 
-    pub fn pow(&self, pow: f64) -> Option<Complex> {
+    /// The exponential function, `eᶻ = eᵃ(cos b + i sin b)` for `z = a + bi`.
+    pub fn exp(&self) -> Result<Self, ComplexError> {
+        finite(self.ieee_exp())
+    }
+
+    /// The principal natural logarithm, `ln|z| + i arg(z)`, with the argument
+    /// in `(-π, π]` so the branch cut lies along the negative real axis. The
+    /// logarithm of zero is a pole, so gives a division by zero error.
+    pub fn ln(&self) -> Result<Self, ComplexError> {
+        if *self == Self::ZERO {
+            return Err(ComplexError::DivisionByZero);
+        }
+
+        finite(self.ieee_ln())
+    }
+
+    /// The principal base 10 logarithm, `ln(z) / ln(10)`.
+    pub fn log10(&self) -> Result<Self, ComplexError> {
+        let c = self.ln()? / T::ln_10();
+
+        finite_complex_or_err(c.re, c.im)
+    }
+
+    /// The principal logarithm to a real base, `ln(z) / ln(base)`. Bases that
+    /// are zero, negative or one are invalid.
+    pub fn log(&self, base: T) -> Result<Self, ComplexError> {
+        if base <= T::ZERO || base == T::ONE {
+            return Err(ComplexError::InvalidArgument(format!("{base} cannot be a logarithm base")));
+        }
+
+        let c = self.ln()? / base.ln();
+
+        finite_complex_or_err(c.re, c.im)
+    }
+
+    /// The principal square root, which has a non-negative real part. The
+    /// branch cut lies along the negative real axis, where the sign of the
+    /// imaginary part (including a signed zero) picks the side of the cut, so
+    /// `sqrt(-4 + 0i) = 2i` and `sqrt(-4 - 0i) = -2i`.
+    pub fn sqrt(&self) -> Result<Self, ComplexError> {
+        finite(self.ieee_sqrt())
+    }
+
+    /// All `n` of the n-th roots of this number, starting with the principal
+    /// root and going anticlockwise. There are no roots when `n` is zero.
+    pub fn roots(&self, n: u32) -> impl Iterator<Item = Self> + use<T> {
         let polar = self.to_polar();
-        let p = polar.r.powf(pow);
-        let theta = polar.theta * pow;
+        let n_t = T::from_f64(n as f64);
+        let r = polar.r.powf(T::ONE / n_t);
+        let half_turns = polar.theta / T::pi();
+
+        (0..n).map(move |k| {
+            let (sin, cos) = sin_cos_pi((half_turns + T::from_f64(2.0 * k as f64)) / n_t);
+            Complex::new(r * cos, r * sin)
+        })
+    }
+
+    /// The `n` n-th roots of unity, `e^(2πik/n)` for `k = 0..n`, starting
+    /// from one.
+    pub fn roots_of_unity(n: u32) -> impl Iterator<Item = Self> + use<T> {
+        Self::ONE.roots(n)
+    }
+
+    /// The principal cube root, `∛|z| (cos(θ/3) + i sin(θ/3))` for
+    /// `θ = arg(z)` in `(-π, π]`. Note that this is not the real cube root for
+    /// negative reals, so `cbrt(-8) = 1 + 1.732...i` rather than `-2`.
+    pub fn cbrt(&self) -> Result<Self, ComplexError> {
+        finite(self.ieee_cbrt())
+    }
+
+    /// Multiply following C99 Annex G, so an infinite operand gives an
+    /// infinite result even where the textbook formula makes `NaN + NaN i`,
+    /// as it does for `∞ × (1 + i)`.
+    pub fn ieee_mul(&self, other: Self) -> Self {
+        let (mut a, mut b, mut c, mut d) = (self.re, self.im, other.re, other.im);
+        let (ac, bd, ad, bc) = (a * c, b * d, a * d, b * c);
+        let (re, im) = (ac - bd, ad + bc);
+
+        if !(re.is_nan() && im.is_nan()) {
+            return Complex::new(re, im);
+        }
+
+        let mut recalc = false;
+        if a.is_infinite() || b.is_infinite() {
+            (a, b) = (box_infinity(a), box_infinity(b));
+            (c, d) = (nan_to_zero(c), nan_to_zero(d));
+            recalc = true;
+        }
+        if c.is_infinite() || d.is_infinite() {
+            (c, d) = (box_infinity(c), box_infinity(d));
+            (a, b) = (nan_to_zero(a), nan_to_zero(b));
+            recalc = true;
+        }
+        if !recalc && [ac, bd, ad, bc].iter().any(|x| x.is_infinite()) {
+            // the products overflowed, so the result is infinite
+            (a, b, c, d) = (nan_to_zero(a), nan_to_zero(b), nan_to_zero(c), nan_to_zero(d));
+            recalc = true;
+        }
+
+        if recalc {
+            Complex::new(T::infinity() * (a * c - b * d), T::infinity() * (a * d + b * c))
+        } else {
+            Complex::new(re, im)
+        }
+    }
+
+    /// Divide following C99 Annex G, so that a non-zero number over zero is
+    /// infinite, an infinite one over a finite one stays infinite, and a
+    /// finite one over an infinite one is zero.
+    pub fn ieee_div(&self, other: Self) -> Self {
+        let q = *self / other;
+
+        if !(q.re.is_nan() && q.im.is_nan()) {
+            return q;
+        }
 
-        if p.is_finite() && theta.is_finite() {
-            let norm_theta = theta - 2.0 * PI * (theta / PI).floor();
-            if norm_theta.is_finite() {
-                Some(PolarComplex::new(p, norm_theta).to_complex())
+        let (mut a, mut b, mut c, mut d) = (self.re, self.im, other.re, other.im);
+        if c == T::ZERO && d == T::ZERO && !(a.is_nan() && b.is_nan()) {
+            let inf = T::infinity().copysign(c);
+            Complex::new(inf * a, inf * b)
+        } else if (a.is_infinite() || b.is_infinite()) && c.is_finite() && d.is_finite() {
+            (a, b) = (box_infinity(a), box_infinity(b));
+            Complex::new(T::infinity() * (a * c + b * d), T::infinity() * (b * c - a * d))
+        } else if (c.is_infinite() || d.is_infinite()) && a.is_finite() && b.is_finite() {
+            (c, d) = (box_infinity(c), box_infinity(d));
+            Complex::new(T::ZERO * (a * c + b * d), T::ZERO * (b * c - a * d))
+        } else {
+            q
+        }
+    }
+
+    /// Raise to a real power with no checks on the result, so that zero to a
+    /// negative power is infinite. As with `pow`, a whole number power goes by
+    /// way of repeated squaring, so `(1 + i)⁸` is exactly 16, and any other by
+    /// way of the polar form.
+    pub fn ieee_pow(&self, pow: T) -> Self {
+        if *self == Self::ZERO {
+            return if pow > T::ZERO {
+                Self::ZERO
+            } else if pow == T::ZERO {
+                Self::ONE
             } else {
-                None
+                Complex::new(T::infinity(), T::ZERO)
+            };
+        }
+
+        if let Some(n) = as_i32(pow) {
+            let base = if n < 0 { Self::ONE.ieee_div(*self) } else { *self };
+            return base.ieee_powu(n.unsigned_abs());
+        }
+
+        let polar = self.to_polar();
+        PolarComplex::new(polar.r.powf(pow), polar.theta * pow).to_complex()
+    }
+
+    fn ieee_powu(&self, n: u32) -> Self {
+        let mut result = Self::ONE;
+        let mut base = *self;
+        let mut n = n;
+
+        while n > 0 {
+            if n & 1 == 1 {
+                result = result.ieee_mul(base);
             }
+            n >>= 1;
+            if n > 0 {
+                base = base.ieee_mul(base);
+            }
+        }
+
+        result
+    }
+
+    // The functions below are the unchecked forms of `exp`, `ln` and the
+    // others, which those check the results of. In ieee mode infinities and
+    // NaNs carry through them, and the logarithm of zero is -∞.
+
+    pub fn ieee_exp(&self) -> Self {
+        let s = self.re.exp();
+
+        // keep a real argument real, rather than let an overflowing eᵃ turn
+        // into NaN when multiplied by sin 0
+        if self.im == T::ZERO {
+            return Complex::new(s, self.im);
+        }
+
+        Complex::new(s * self.im.cos(), s * self.im.sin())
+    }
+
+    pub fn ieee_ln(&self) -> Self {
+        Complex::new(self.re.hypot(self.im).ln(), self.im.atan2(self.re))
+    }
+
+    pub fn ieee_log10(&self) -> Self {
+        self.ieee_ln() / T::ln_10()
+    }
+
+    pub fn ieee_sqrt(&self) -> Self {
+        if self.re == T::ZERO && self.im == T::ZERO {
+            return Complex::new(T::ZERO, self.im);
+        }
+
+        // t = √((|a| + |z|) / 2) avoids cancellation in whichever part is
+        // computed from it; the other part then follows from b = 2 * re * im.
+        let t = ((self.re.abs() + self.re.hypot(self.im)) / two()).sqrt();
+
+        if self.re >= T::ZERO {
+            Complex::new(t, self.im / (two::<T>() * t))
         } else {
-            None
+            Complex::new(self.im.abs() / (two::<T>() * t), t.copysign(self.im))
         }
     }
 
-    
-    pub fn powc(&self, pow: Complex) -> Option<Complex> {
-        let polar = self.to_polar();
-        let q = Complex::new(polar.r.ln(), polar.theta);
-        let r = q.mul(pow)?;
-        let s = r.re.exp();
-        let t = r.im;
+    pub fn ieee_cbrt(&self) -> Self {
+        let r = self.re.hypot(self.im).cbrt();
+        let theta = self.im.atan2(self.re) / T::from_f64(3.0);
 
-        finite_complex_or_none(s * t.cos(), s * t.sin())
+        Complex::new(r * theta.cos(), r * theta.sin())
     }
 
-    // Uses:
-    // 
-    // a₁ + b₁i       a₁a₂ + b₁b₂     a₂b₁ - a₁b₂
-    // -------- =    ------------- + ------------ i
-    // a₂ + b₂i        a₂² + b₂²       a₂² + b₂²
-    // 
-    pub fn div(&self, other: Complex) -> Option<Complex> {
-        let denom = other.re * other.re + other.im * other.im;
-        let re_num = self.re * other.re + self.im * other.im;
-        let im_num = other.re * self.im - self.re * other.im;
+    /// Add under the given arithmetic mode.
+    pub fn add_in(&self, other: Self, mode: ArithmeticMode) -> Result<Self, ComplexError> {
+        match mode {
+            ArithmeticMode::Strict => self.checked_add(other),
+            ArithmeticMode::Ieee => Ok(*self + other),
+        }
+    }
 
-        let re = re_num / denom;
-        let im = im_num / denom;
+    /// Subtract under the given arithmetic mode.
+    pub fn sub_in(&self, other: Self, mode: ArithmeticMode) -> Result<Self, ComplexError> {
+        match mode {
+            ArithmeticMode::Strict => self.checked_sub(other),
+            ArithmeticMode::Ieee => Ok(*self - other),
+        }
+    }
 
-        finite_complex_or_none(re, im)
+    /// Multiply under the given arithmetic mode.
+    pub fn mul_in(&self, other: Self, mode: ArithmeticMode) -> Result<Self, ComplexError> {
+        match mode {
+            ArithmeticMode::Strict => self.checked_mul(other),
+            ArithmeticMode::Ieee => Ok(self.ieee_mul(other)),
+        }
     }
 
-}
+    /// Divide under the given arithmetic mode.
+    pub fn div_in(&self, other: Self, mode: ArithmeticMode) -> Result<Self, ComplexError> {
+        match mode {
+            ArithmeticMode::Strict => self.checked_div(other),
+            ArithmeticMode::Ieee => Ok(self.ieee_div(other)),
+        }
+    }
 
-fn finite_complex_or_none(re: f64, im: f64) -> Option<Complex> {
-    if re.is_finite() && im.is_finite() {
-        Some(Complex::new(re, im))
-    } else {
-        None
+    /// Raise to a power under the given arithmetic mode. A whole number power
+    /// goes by way of `powi`, and any other by way of `powc`.
+    pub fn pow_in(&self, pow: Self, mode: ArithmeticMode) -> Result<Self, ComplexError> {
+        match (mode, as_i32(pow.re)) {
+            (ArithmeticMode::Ieee, _) => Ok(self.ieee_powc(pow)),
+            (_, Some(n)) if pow.im == T::ZERO => self.powi(n),
+            _ => self.powc(pow),
+        }
+    }
+
+    /// Divide, returning an error if dividing by zero or if either part of the
+    /// result is not finite.
+    pub fn checked_div(&self, other: Self) -> Result<Self, ComplexError> {
+        if other == Self::ZERO {
+            return Err(ComplexError::DivisionByZero);
+        }
+
+        let c = *self / other;
+
+        finite_complex_or_err(c.re, c.im)
     }
+
+}
+
+fn two<T: Float>() -> T {
+    T::ONE + T::ONE
+}
+
+// The whole number that x is, if it is one that fits an i32.
+fn as_i32<T: Float>(x: T) -> Option<i32> {
+    let f = x.to_f64();
+    (x.fract() == T::ZERO && f >= i32::MIN as f64 && f <= i32::MAX as f64).then_some(f as i32)
 }
 
-fn checked_add(a: f64, b: f64) -> Option<f64> {
-    let c = a + b;
-    c.is_finite().then_some(c)
+// Annex G's "box" for an infinite part: ±1 for an infinity and ±0 otherwise,
+// keeping the sign.
+fn box_infinity<T: Float>(x: T) -> T {
+    if x.is_infinite() { T::ONE } else { T::ZERO }.copysign(x)
 }
 
-fn checked_sub(a: f64, b: f64) -> Option<f64> {
-    let c = a - b;
-    c.is_finite().then_some(c)
+// A NaN part as a zero, so it can't swamp an infinite product.
+fn nan_to_zero<T: Float>(x: T) -> T {
+    if x.is_nan() { T::ZERO.copysign(x) } else { x }
 }
 
-/*fn checked_mul(a: f64, b: f64) -> Option<f64> {
-    let c = a * b;
-    c.is_finite().then_some(c)
+/// `z`, or an error describing why one of its parts is not finite.
+pub(crate) fn finite<T: Float>(z: Complex<T>) -> Result<Complex<T>, ComplexError> {
+    finite_complex_or_err(z.re, z.im)
 }
 
-fn checked_div(a: f64, b: f64) -> Option<f64> {
-    let c = a / b;
-    c.is_finite().then_some(c)
-}*/
+/// The complex number with the given parts, or an error describing why one of
+/// them is not finite.
+pub(crate) fn finite_complex_or_err<T: Float>(re: T, im: T) -> Result<Complex<T>, ComplexError> {
+    if re.is_nan() || im.is_nan() {
+        Err(ComplexError::NaNProduced)
+    } else if re.is_infinite() || im.is_infinite() {
+        Err(ComplexError::Overflow)
+    } else {
+        Ok(Complex::new(re, im))
+    }
+}
+
+// Sine and cosine of πx, which are exact when x is a multiple of a half so that
+// roots landing on an axis don't pick up a tiny stray part.
+fn sin_cos_pi<T: Float>(x: T) -> (T, T) {
+    let x = x.rem_euclid(two());
+    let half = T::from_f64(0.5);
+
+    if x == T::ZERO {
+        (T::ZERO, T::ONE)
+    } else if x == half {
+        (T::ONE, T::ZERO)
+    } else if x == T::ONE {
+        (T::ZERO, -T::ONE)
+    } else if x == T::ONE + half {
+        (-T::ONE, T::ZERO)
+    } else {
+        (x * T::pi()).sin_cos()
+    }
+}
 
-fn im_to_string(im: f64, magnitude: usize, precision: usize) -> String {
-    match im {
-        1.0 => "i".to_owned(),
-        -1.0 => "-i".to_owned(),
-        _ => format!("{}i", format_f64(im, magnitude, precision)).to_owned(),
+// Anything that prints as 1 is shown as a bare i, so a last-place rounding
+// error doesn't turn i into 1i.
+fn im_to_string(im: String) -> String {
+    match im.as_str() {
+        "1" => "i".to_owned(),
+        "-1" => "-i".to_owned(),
+        s => format!("{}i", s),
     }
 }
 
-impl fmt::Display for Complex {
+impl<T: Num> fmt::Display for Complex<T> {
     fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
         let magnitude = fmt.width().unwrap_or(12);
         let precision = fmt.precision().unwrap_or(6);
@@ -175,7 +710,7 @@ impl fmt::Display for Complex {
     }
 }
 
-impl fmt::Display for PolarComplex {
+impl<T: Float> fmt::Display for PolarComplex<T> {
     fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
         let magnitude = fmt.width().unwrap_or(12);
         let precision = fmt.precision().unwrap_or(6);
@@ -184,6 +719,224 @@ impl fmt::Display for PolarComplex {
     }
 }
 
+impl<T: Num> From<T> for Complex<T> {
+    fn from(re: T) -> Self {
+        Complex::new(re, T::ZERO)
+    }
+}
+
+impl<T: Num> Add for Complex<T> {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        Complex::new(self.re + other.re, self.im + other.im)
+    }
+}
+
+impl<T: Num> Sub for Complex<T> {
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self {
+        Complex::new(self.re - other.re, self.im - other.im)
+    }
+}
+
+impl<T: Num> Mul for Complex<T> {
+    type Output = Self;
+
+    fn mul(self, other: Self) -> Self {
+        Complex::new(
+            self.re * other.re - self.im * other.im,
+            self.re * other.im + other.re * self.im)
+    }
+}
+
+impl<T: Float> Div for Complex<T> {
+    type Output = Self;
+
+    /// Baudin and Smith's robust version of Smith's algorithm, from "A Robust
+    /// Complex Division in Scilab" (2012). It never forms `c² + d²`, and
+    /// scales operands near the ends of the exponent range, so the quotient
+    /// is accurate whenever it's representable.
+    fn div(self, other: Self) -> Self {
+        let (mut a, mut b) = (self.re, self.im);
+        let (mut c, mut d) = (other.re, other.im);
+
+        let ab = a.abs().max(b.abs());
+        let cd = c.abs().max(d.abs());
+        let half = T::from_f64(0.5);
+        let two = two::<T>();
+        let mut s = T::ONE;
+
+        if ab >= T::max_value() * half {
+            (a, b, s) = (a * half, b * half, s * two);
+        }
+        if cd >= T::max_value() * half {
+            (c, d, s) = (c * half, d * half, s * half);
+        }
+
+        let tiny = T::min_positive() * two / T::epsilon();
+        let boost = two / (T::epsilon() * T::epsilon());
+        if ab <= tiny {
+            (a, b, s) = (a * boost, b * boost, s / boost);
+        }
+        if cd <= tiny {
+            (c, d, s) = (c * boost, d * boost, s * boost);
+        }
+
+        let (re, im) = if d.abs() <= c.abs() {
+            smith_div(a, b, c, d)
+        } else {
+            let (re, im) = smith_div(b, a, d, c);
+            (re, -im)
+        };
+
+        Complex::new(re * s, im * s)
+    }
+}
+
+// (a + bi) / (c + di) for |d| <= |c|, by way of r = d / c.
+fn smith_div<T: Float>(a: T, b: T, c: T, d: T) -> (T, T) {
+    let r = d / c;
+    let t = T::ONE / (c + d * r);
+
+    (smith_part(a, b, c, d, r, t), smith_part(b, -a, c, d, r, t))
+}
+
+// One part of the quotient, (a + b * r) * t, without losing b * r to underflow.
+fn smith_part<T: Float>(a: T, b: T, c: T, d: T, r: T, t: T) -> T {
+    if r != T::ZERO {
+        let br = b * r;
+        if br != T::ZERO {
+            (a + br) * t
+        } else {
+            a * t + (b * t) * r
+        }
+    } else if d != T::ZERO {
+        (a + d * (b / c)) * t
+    } else {
+        // a real divisor, where d * (b / c) could be 0 × ∞
+        a * t
+    }
+}
+
+impl<T: Num> Neg for Complex<T> {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        Complex::new(-self.re, -self.im)
+    }
+}
+
+impl<T: Num> Add<T> for Complex<T> {
+    type Output = Self;
+
+    fn add(self, other: T) -> Self {
+        Complex::new(self.re + other, self.im)
+    }
+}
+
+impl<T: Num> Sub<T> for Complex<T> {
+    type Output = Self;
+
+    fn sub(self, other: T) -> Self {
+        Complex::new(self.re - other, self.im)
+    }
+}
+
+impl<T: Num> Mul<T> for Complex<T> {
+    type Output = Self;
+
+    fn mul(self, other: T) -> Self {
+        Complex::new(self.re * other, self.im * other)
+    }
+}
+
+impl<T: Float> Div<T> for Complex<T> {
+    type Output = Self;
+
+    fn div(self, other: T) -> Self {
+        Complex::new(self.re / other, self.im / other)
+    }
+}
+
+// A real on the left of a complex number can't be covered generically, so is
+// implemented for each of the primitive types.
+macro_rules! real_op_complex {
+    ($t:ident) => {
+        impl Add<Complex<$t>> for $t {
+            type Output = Complex<$t>;
+
+            fn add(self, other: Complex<$t>) -> Complex<$t> {
+                Complex::new(self + other.re, other.im)
+            }
+        }
+
+        impl Sub<Complex<$t>> for $t {
+            type Output = Complex<$t>;
+
+            fn sub(self, other: Complex<$t>) -> Complex<$t> {
+                Complex::new(self - other.re, -other.im)
+            }
+        }
+
+        impl Mul<Complex<$t>> for $t {
+            type Output = Complex<$t>;
+
+            fn mul(self, other: Complex<$t>) -> Complex<$t> {
+                Complex::new(self * other.re, self * other.im)
+            }
+        }
+    };
+}
+
+real_op_complex!(f32);
+real_op_complex!(f64);
+real_op_complex!(i8);
+real_op_complex!(i16);
+real_op_complex!(i32);
+real_op_complex!(i64);
+real_op_complex!(i128);
+
+impl Div<Complex<f32>> for f32 {
+    type Output = Complex<f32>;
+
+    fn div(self, other: Complex<f32>) -> Complex<f32> {
+        Complex::from(self) / other
+    }
+}
+
+impl Div<Complex<f64>> for f64 {
+    type Output = Complex<f64>;
+
+    fn div(self, other: Complex<f64>) -> Complex<f64> {
+        Complex::from(self) / other
+    }
+}
+
+// The assignment operators all forward to the binary operators above, for
+// both complex and real right hand sides.
+macro_rules! assign_op {
+    ($trait:ident, $method:ident, $op:tt, $bound:ident) => {
+        impl<T: $bound> $trait for Complex<T> {
+            fn $method(&mut self, other: Self) {
+                *self = *self $op other;
+            }
+        }
+
+        impl<T: $bound> $trait<T> for Complex<T> {
+            fn $method(&mut self, other: T) {
+                *self = *self $op other;
+            }
+        }
+    };
+}
+
+assign_op!(AddAssign, add_assign, +, Num);
+assign_op!(SubAssign, sub_assign, -, Num);
+assign_op!(MulAssign, mul_assign, *, Num);
+assign_op!(DivAssign, div_assign, /, Float);
+
 #[cfg(test)]
 mod tests {
     use core::f64;
@@ -217,9 +970,9 @@ mod tests {
         c, expected,
         case::zero(Complex::new(0.0, 0.0), PolarComplex::new(0.0, 0.0)),
         case::one(Complex::new(1.0, 0.0), PolarComplex::new(1.0, 0.0)),
-        case::i(Complex::new(0.0, 1.0), PolarComplex::new(1.0, 1.57)),
+        case::i(Complex::new(0.0, 1.0), PolarComplex::new(1.0, 1.571)),
         case::minus_i(Complex::new(0.0, -1.0), PolarComplex::new(1.0, -1.571)),
-        case::minus_one(Complex::new(-1.0, 0.0), PolarComplex::new(1.0, 3.142)),
+        case::minus_one(Complex::new(-1.0, 0.0), PolarComplex::new(1.0, f64::consts::PI)),
         case::one_plus_i(Complex::new(1.0, 1.0), PolarComplex::new(1.414, 0.785)),
         case::minus_one_minus_i(Complex::new(-1.0, -1.0), PolarComplex::new(1.414, -2.357)),
         case::a_bigger_number(Complex::new(-123.0, 26.0), PolarComplex::new(125.718, 2.933))
@@ -266,78 +1019,647 @@ mod tests {
 
     #[rstest(
         a, b, expected,
-        case::add(Complex::new(1.0, 1.0), Complex::new(2.0, -1.0), Some(Complex::new(3.0, 0.0))),
-        case::add_infinity_none(Complex::new(1.0, 1.0), Complex::new(2.0, f64::INFINITY), None),
-        case::add_nan_none(Complex::new(f64::NAN, 1.0), Complex::new(2.0, -1.0), None)
+        case::add(Complex::new(1.0, 1.0), Complex::new(2.0, -1.0), Ok(Complex::new(3.0, 0.0))),
+        case::add_infinity_err(Complex::new(1.0, 1.0), Complex::new(2.0, f64::INFINITY), Err(ComplexError::Overflow)),
+        case::add_overflow_err(Complex::new(f64::MAX, 1.0), Complex::new(f64::MAX, -1.0), Err(ComplexError::Overflow)),
+        case::add_nan_err(Complex::new(f64::NAN, 1.0), Complex::new(2.0, -1.0), Err(ComplexError::NaNProduced))
     )]
-    fn add(a: Complex, b: Complex, expected: Option<Complex>) {
-        let result = a.add(b);
+    fn checked_add(a: Complex, b: Complex, expected: Result<Complex, ComplexError>) {
+        let result = a.checked_add(b);
         assert_eq!(expected, result);
     }
 
     #[rstest(
         a, b, expected,
-        case::sub(Complex::new(1.0, 1.0), Complex::new(2.0, -1.0), Some(Complex::new(-1.0, 2.0))),
-        case::sub_infinity_none(Complex::new(1.0, 1.0), Complex::new(2.0, f64::INFINITY), None),
-        case::sub_nan_none(Complex::new(f64::NAN, 1.0), Complex::new(2.0, -1.0), None)
+        case::sub(Complex::new(1.0, 1.0), Complex::new(2.0, -1.0), Ok(Complex::new(-1.0, 2.0))),
+        case::sub_infinity_err(Complex::new(1.0, 1.0), Complex::new(2.0, f64::INFINITY), Err(ComplexError::Overflow)),
+        case::sub_nan_err(Complex::new(f64::NAN, 1.0), Complex::new(2.0, -1.0), Err(ComplexError::NaNProduced))
     )]
-    fn sub(a: Complex, b: Complex, expected: Option<Complex>) {
-        let result = a.sub(b);
+    fn checked_sub(a: Complex, b: Complex, expected: Result<Complex, ComplexError>) {
+        let result = a.checked_sub(b);
         assert_eq!(expected, result);
     }
 
     #[rstest(
         a, b, expected,
-        case::one(Complex::new(1.0, 0.0), 17.0, Some(Complex::new(1.0, 0.0))),
-        case::i(Complex::new(0.0, 1.0), 2.0, Some(Complex::new(-1.0, 0.0)))
+        case::one(Complex::new(1.0, 0.0), 17.0, Complex::new(1.0, 0.0)),
+        case::i(Complex::new(0.0, 1.0), 2.0, Complex::new(-1.0, 0.0)),
+        case::root_of_minus_one(Complex::new(-1.0, 0.0), 0.5, Complex::new(0.0, 1.0)),
+        case::root_below_cut(Complex::new(-1.0, -0.0), 0.5, Complex::new(0.0, -1.0)),
+        case::negative_angle(Complex::new(-1.0, -1.0), 0.5, Complex::new(0.455090, -1.098684)),
+        case::negative_fraction(Complex::new(0.0, -8.0), -1.0 / 3.0, Complex::new(0.433013, 0.25)),
+        case::large_angle(Complex::new(-1.0, 1.0), 2.5, Complex::new(2.197368, -0.910180))
+    )]
+    fn pow(a: Complex, b: f64, expected: Complex) {
+        let result = a.pow(b).unwrap();
+        assert_complex_close!(expected, result, 0.000001);
+    }
+
+    // whole number powers come out exactly
+    #[rstest(
+        a, b, expected,
+        case::square(Complex::new(3.0, 4.0), 2.0, Complex::new(-7.0, 24.0)),
+        case::eighth(Complex::new(1.0, 1.0), 8.0, Complex::new(16.0, 0.0)),
+        case::cube_of_i(Complex::new(0.0, 1.0), 3.0, Complex::new(0.0, -1.0)),
+        case::zeroth(Complex::new(3.0, 4.0), 0.0, Complex::ONE),
+        case::negative(Complex::new(2.0, 0.0), -3.0, Complex::new(0.125, 0.0)),
+        case::negative_complex(Complex::new(1.0, 1.0), -2.0, Complex::new(0.0, -0.5))
+    )]
+    fn pow_exact(a: Complex, b: f64, expected: Complex) {
+        assert_eq!(Ok(expected), a.pow(b));
+    }
+
+    #[rstest(
+        a, n, expected,
+        case::zeroth(Complex::new(0.0, 0.0), 0, Ok(Complex::ONE)),
+        case::eighth(Complex::new(1.0, 1.0), 8, Ok(Complex::new(16.0, 0.0))),
+        case::odd(Complex::new(1.0, 2.0), 5, Ok(Complex::new(41.0, -38.0))),
+        case::negative(Complex::new(0.0, 2.0), -3, Ok(Complex::new(0.0, 0.125))),
+        case::min(Complex::new(1.0, 0.0), i32::MIN, Ok(Complex::ONE)),
+        case::zero_negative(Complex::new(0.0, 0.0), -1, Err(ComplexError::DivisionByZero)),
+        case::overflow(Complex::new(2.0, 2.0), 2000, Err(ComplexError::Overflow))
+    )]
+    fn powi(a: Complex, n: i32, expected: Result<Complex, ComplexError>) {
+        assert_eq!(expected, a.powi(n));
+    }
+
+    #[rstest(
+        b, expected,
+        case::zeroth(0.0, Ok(Complex::ONE)),
+        case::whole(2.0, Ok(Complex::ZERO)),
+        case::fraction(2.5, Ok(Complex::ZERO)),
+        case::negative(-1.0, Err(ComplexError::DivisionByZero)),
+        case::negative_fraction(-0.5, Err(ComplexError::DivisionByZero))
+    )]
+    fn pow_of_zero(b: f64, expected: Result<Complex, ComplexError>) {
+        assert_eq!(expected, Complex::ZERO.pow(b));
+    }
+
+    #[rstest(
+        b, expected,
+        case::real(Complex::new(2.0, 0.0), Ok(Complex::ZERO)),
+        case::zeroth(Complex::ZERO, Ok(Complex::ONE)),
+        case::positive(Complex::new(0.5, 3.0), Ok(Complex::ZERO)),
+        case::negative(Complex::new(-0.5, 3.0), Err(ComplexError::DivisionByZero)),
+        case::imaginary(Complex::new(0.0, 1.0), Err(ComplexError::InvalidArgument("0 to the power {i} is undefined".to_owned())))
     )]
-    fn pow(a: Complex, b: f64, expected: Option<Complex>) {
-        let result = a.pow(b);
-        assert_complex_close!(expected.unwrap(), result.unwrap(), 0.000001);
+    fn powc_of_zero(b: Complex, expected: Result<Complex, ComplexError>) {
+        assert_eq!(expected, Complex::ZERO.powc(b));
+    }
+
+    const BASES: [Complex; 8] = [
+        Complex::new(1.0, 0.0),
+        Complex::new(-2.0, 0.0),
+        Complex::new(0.0, 1.5),
+        Complex::new(0.3, -0.4),
+        Complex::new(-1.25, 2.5),
+        Complex::new(-3.0, -0.5),
+        Complex::new(7.0, 1.0),
+        Complex::new(-0.1, 0.0),
+    ];
+
+    fn relatively_close(x: Complex, y: Complex) -> bool {
+        (x - y).to_polar().r <= 1e-12 * x.to_polar().r.max(1.0)
+    }
+
+    #[test]
+    fn pow_matches_repeated_multiplication() {
+        for z in BASES {
+            let mut product = Complex::ONE;
+            for n in 0..=12 {
+                assert!(relatively_close(product, z.pow(n as f64).unwrap()), "{z}^{n}");
+                assert!(relatively_close(Complex::ONE / product, z.pow(-n as f64).unwrap()), "{z}^-{n}");
+                product *= z;
+            }
+        }
+    }
+
+    #[test]
+    fn pow_of_roots_recovers_base() {
+        for z in BASES {
+            for n in 2..=7 {
+                let root = z.pow(1.0 / n as f64).unwrap();
+                // the principal root lies within π/n of the positive real axis
+                assert!(root.im.atan2(root.re).abs() <= f64::consts::PI / n as f64 + 1e-12, "{z}^(1/{n})");
+                assert!(relatively_close(z, root.pow(n as f64).unwrap()), "({z}^(1/{n}))^{n}");
+            }
+        }
+    }
+
+    #[test]
+    fn pow_of_sum_is_product_of_pows() {
+        for z in BASES {
+            for (a, b) in [(0.5, 0.25), (1.5, -0.75), (-0.3, 2.2), (1.0 / 3.0, 2.0 / 3.0)] {
+                let product = z.pow(a).unwrap() * z.pow(b).unwrap();
+                assert!(relatively_close(product, z.pow(a + b).unwrap()), "{z}^{a} * {z}^{b}");
+                assert!(relatively_close(z.pow(a).unwrap(), z.powc(Complex::new(a, 0.0)).unwrap()), "{z}^{a}");
+            }
+        }
+    }
+
+    #[test]
+    fn pow_overflow_err() {
+        assert_eq!(Err(ComplexError::Overflow), Complex::new(10.0, 0.0).pow(1000.0));
     }
 
     #[rstest(
         a, b, expected,
-        case::one(Complex::new(1.0, 0.0), Complex::new(17.0, 0.0), Some(Complex::new(1.0, 0.0))),
-        case::i(Complex::new(0.0, 1.0), Complex::new(2.0, 0.0), Some(Complex::new(-1.0, 0.0))),
-        case::i(Complex::new(2.0, 3.0), Complex::new(3.0, 2.0), Some(Complex::new(4.714144, -4.569828)))
+        case::one(Complex::new(1.0, 0.0), Complex::new(17.0, 0.0), Complex::new(1.0, 0.0)),
+        case::i(Complex::new(0.0, 1.0), Complex::new(2.0, 0.0), Complex::new(-1.0, 0.0)),
+        case::i(Complex::new(2.0, 3.0), Complex::new(3.0, 2.0), Complex::new(4.714144, -4.569828))
     )]
-    fn powc(a: Complex, b: Complex, expected: Option<Complex>) {
-        let result = a.powc(b);
-        assert_complex_close!(expected.unwrap(), result.unwrap(), 0.0001);
+    fn powc(a: Complex, b: Complex, expected: Complex) {
+        let result = a.powc(b).unwrap();
+        assert_complex_close!(expected, result, 0.0001);
     }
 
     #[rstest(
         a, b, expected,
-        case::mul(Complex::new(1.0, 1.0), Complex::new(2.0, -1.0), Some(Complex::new(3.0, 1.0))),
-        case::mul_overflow(Complex::new(1.1, 1.0), Complex::new(f64::MAX, -1.0), None),
-        case::sub_infinity_none(Complex::new(1.0, 1.0), Complex::new(2.0, f64::INFINITY), None),
-        case::sub_nan_none(Complex::new(f64::NAN, 1.0), Complex::new(2.0, -1.0), None)
+        case::mul(Complex::new(1.0, 1.0), Complex::new(2.0, -1.0), Ok(Complex::new(3.0, 1.0))),
+        case::mul_overflow(Complex::new(1.1, 1.0), Complex::new(f64::MAX, -1.0), Err(ComplexError::Overflow)),
+        case::mul_infinity_err(Complex::new(1.0, 1.0), Complex::new(2.0, f64::INFINITY), Err(ComplexError::Overflow)),
+        case::mul_nan_err(Complex::new(f64::NAN, 1.0), Complex::new(2.0, -1.0), Err(ComplexError::NaNProduced))
     )]
-    fn mul(a: Complex, b: Complex, expected: Option<Complex>) {
-        let result = a.mul(b);
+    fn checked_mul(a: Complex, b: Complex, expected: Result<Complex, ComplexError>) {
+        let result = a.checked_mul(b);
         assert_eq!(expected, result);
     }
 
     #[rstest(
         a, b, expected,
-        case::pow(Complex::new(2.0, 1.0), 3.0, Some(Complex::new(2.0, 11.0)))
+        case::pow(Complex::new(2.0, 1.0), 3.0, Complex::new(2.0, 11.0))
     )]
-    fn power(a: Complex, b: f64, expected: Option<Complex>) {
-        let result = a.pow(b);
-        assert_complex_close!(expected.unwrap(), result.unwrap(), 0.001);
+    fn power(a: Complex, b: f64, expected: Complex) {
+        let result = a.pow(b).unwrap();
+        assert_complex_close!(expected, result, 0.001);
+    }
+
+    const INF: f64 = f64::INFINITY;
+    const NAN: f64 = f64::NAN;
+
+    // compares NaNs as equal, and zeros by sign
+    fn same(a: Complex, b: Complex) -> bool {
+        let same_part = |x: f64, y: f64| (x.is_nan() && y.is_nan()) || (x == y && x.is_sign_negative() == y.is_sign_negative());
+        same_part(a.re, b.re) && same_part(a.im, b.im)
     }
 
     #[rstest(
         a, b, expected,
-        case::div(Complex::new(3.0, 1.0), Complex::new(2.0, -1.0), Some(Complex::new(1.0, 1.0))),
-        case::div_overflow(Complex::new(1.1, 1.0), Complex::new(f64::MAX, -1.0), None),
-        case::div_by_zero(Complex::new(1.1, 1.0), Complex::ZERO, None),
-        case::div_infinity_none(Complex::new(1.0, 1.0), Complex::new(2.0, f64::INFINITY), None),
-        case::div_nan_none(Complex::new(f64::NAN, 1.0), Complex::new(2.0, -1.0), None)
-    )]
-    fn div(a: Complex, b: Complex, expected: Option<Complex>) {
-        let result = a.div(b);
+        case::finite(Complex::new(1.0, 2.0), Complex::new(3.0, 4.0), Complex::new(-5.0, 10.0)),
+        case::infinite_times_finite(Complex::new(INF, 0.0), Complex::new(1.0, 1.0), Complex::new(INF, INF)),
+        case::infinite_nan_times_finite(Complex::new(INF, NAN), Complex::new(2.0, 0.0), Complex::new(INF, NAN)),
+        case::finite_times_infinite(Complex::new(0.0, 1.0), Complex::new(NAN, INF), Complex::new(-INF, NAN)),
+        case::overflowed_products(Complex::new(1e300, 1e300), Complex::new(1e300, -1e300), Complex::new(INF, NAN)),
+        case::nan_and_overflowed_products(Complex::new(NAN, 1e300), Complex::new(1e300, 1e300), Complex::new(-INF, INF)),
+        case::infinite_times_zero(Complex::new(INF, 0.0), Complex::ZERO, Complex::new(NAN, NAN)),
+        case::nan(Complex::new(NAN, NAN), Complex::new(INF, 0.0), Complex::new(NAN, NAN))
+    )]
+    fn ieee_mul(a: Complex, b: Complex, expected: Complex) {
+        let result = a.ieee_mul(b);
+        assert!(same(expected, result), "{:?} != {:?}", result, expected);
+    }
+
+    #[rstest(
+        a, b, expected,
+        case::finite(Complex::new(-5.0, 10.0), Complex::new(3.0, 4.0), Complex::new(1.0, 2.0)),
+        case::by_zero(Complex::new(1.0, -1.0), Complex::ZERO, Complex::new(INF, -INF)),
+        case::by_negative_zero(Complex::new(1.0, 0.0), Complex::new(-0.0, 0.0), Complex::new(-INF, NAN)),
+        case::zero_by_zero(Complex::ZERO, Complex::ZERO, Complex::new(NAN, NAN)),
+        case::infinite_by_finite(Complex::new(INF, NAN), Complex::new(1.0, 1.0), Complex::new(INF, -INF)),
+        case::finite_by_infinite(Complex::new(1.0, 1.0), Complex::new(INF, NAN), Complex::new(0.0, 0.0)),
+        case::infinite_by_infinite(Complex::new(INF, 0.0), Complex::new(INF, 0.0), Complex::new(NAN, 0.0))
+    )]
+    fn ieee_div(a: Complex, b: Complex, expected: Complex) {
+        let result = a.ieee_div(b);
+        assert!(same(expected, result), "{:?} != {:?}", result, expected);
+    }
+
+    #[rstest(
+        a, b, mode, expected,
+        case::strict_by_zero(Complex::new(1.0, 1.0), Complex::ZERO, ArithmeticMode::Strict, Err(ComplexError::DivisionByZero)),
+        case::ieee_by_zero(Complex::new(1.0, 1.0), Complex::ZERO, ArithmeticMode::Ieee, Ok(Complex::new(INF, INF))),
+        case::strict_overflow(Complex::new(1e300, 0.0), Complex::new(1e-300, 0.0), ArithmeticMode::Strict, Err(ComplexError::Overflow)),
+        case::ieee_overflow(Complex::new(1e300, 0.0), Complex::new(1e-300, 0.0), ArithmeticMode::Ieee, Ok(Complex::new(INF, 0.0)))
+    )]
+    fn div_in(a: Complex, b: Complex, mode: ArithmeticMode, expected: Result<Complex, ComplexError>) {
+        assert_eq!(expected, a.div_in(b, mode));
+    }
+
+    // exact powers of two, including subnormals that powi underflows on
+    fn p2(n: i32) -> f64 {
+        2f64.powi(n / 2) * 2f64.powi(n - n / 2)
+    }
+
+    // The hard cases from Baudin and Smith's paper, where the naive formula
+    // overflows or underflows but the quotient is representable.
+    #[rstest(
+        a, b, expected,
+        case::tiny_quotient(Complex::new(1.0, 1.0), Complex::new(1.0, p2(1023)), Complex::new(p2(-1023), -p2(-1023))),
+        case::tiny_divisor(Complex::new(1.0, 1.0), Complex::new(p2(-1023), p2(-1023)), Complex::new(p2(1023), 0.0)),
+        case::huge_and_tiny(Complex::new(p2(1023), p2(-1023)), Complex::new(p2(677), p2(-677)), Complex::new(p2(346), -p2(-1008))),
+        case::huge_both(Complex::new(p2(1023), p2(1023)), Complex::new(1.0, 1.0), Complex::new(p2(1023), 0.0)),
+        case::mixed(Complex::new(p2(1020), p2(-844)), Complex::new(p2(656), p2(-780)), Complex::new(p2(364), -p2(-1072))),
+        case::subnormal_re(Complex::new(p2(-71), p2(1021)), Complex::new(p2(1001), p2(-323)), Complex::new(p2(-1072), p2(20))),
+        case::subnormal(Complex::new(p2(-1074), p2(-1074)), Complex::new(p2(-1073), p2(-1074)), Complex::new(0.6, 0.2)),
+        case::huge_divisor(Complex::new(p2(1015), p2(-989)), Complex::new(p2(1023), p2(1023)), Complex::new(0.001953125, -0.001953125)),
+        case::near_overflow(Complex::new(1.1, 1.0), Complex::new(f64::MAX, -1.0), Complex::new(1.1 / f64::MAX, 1.0 / f64::MAX)),
+        case::tiny_all(Complex::new(p2(-622), p2(-1071)), Complex::new(p2(-343), p2(-798)), Complex::new(1.0295115178936058e-84, 6.971459875150762e-220))
+    )]
+    fn div_hard_cases(a: Complex, b: Complex, expected: Complex) {
+        // within an ulp or two, counting subnormal ulps as well
+        let close = |x: f64, y: f64| (x - y).abs() <= 2.0 * (f64::EPSILON * y.abs()).max(f64::from_bits(1));
+        let result = a / b;
+        assert!(close(result.re, expected.re) && close(result.im, expected.im), "{:?} != {:?}", result, expected);
+    }
+
+    #[rstest(
+        p, r, theta,
+        case::degrees(PolarComplex::from_degrees(2.0, 90.0), 2.0, f64::consts::FRAC_PI_2),
+        case::negative_degrees(PolarComplex::from_degrees(1.0, -45.0), 1.0, -f64::consts::FRAC_PI_4),
+        case::gradians(PolarComplex::from_gradians(3.0, 100.0), 3.0, f64::consts::FRAC_PI_2),
+        case::full_turn(PolarComplex::from_gradians(1.0, 400.0), 1.0, 2.0 * f64::consts::PI)
+    )]
+    fn polar_angle_units(p: PolarComplex, r: f64, theta: f64) {
+        assert_eq!(r, p.r());
+        assert_close!(theta, p.theta(), 1e-15);
+        assert_close!(theta * 180.0 / f64::consts::PI, p.theta_degrees(), 1e-12);
+        assert_close!(theta * 200.0 / f64::consts::PI, p.theta_gradians(), 1e-12);
+    }
+
+    #[rstest(
+        p, expected, expected_positive,
+        case::principal(PolarComplex::new(1.0, 1.0), PolarComplex::new(1.0, 1.0), PolarComplex::new(1.0, 1.0)),
+        case::half_turn(PolarComplex::new(1.0, f64::consts::PI), PolarComplex::new(1.0, f64::consts::PI), PolarComplex::new(1.0, f64::consts::PI)),
+        case::minus_half_turn(PolarComplex::new(1.0, -f64::consts::PI), PolarComplex::new(1.0, f64::consts::PI), PolarComplex::new(1.0, f64::consts::PI)),
+        case::negative(PolarComplex::new(2.0, -1.0), PolarComplex::new(2.0, -1.0), PolarComplex::new(2.0, 2.0 * f64::consts::PI - 1.0)),
+        case::turns(PolarComplex::new(2.0, 7.0), PolarComplex::new(2.0, 7.0 - 2.0 * f64::consts::PI), PolarComplex::new(2.0, 7.0 - 2.0 * f64::consts::PI)),
+        case::many_turns(PolarComplex::new(1.0, -20.0), PolarComplex::new(1.0, 6.0 * f64::consts::PI - 20.0), PolarComplex::new(1.0, 8.0 * f64::consts::PI - 20.0)),
+        case::negative_modulus(PolarComplex::new(-3.0, 0.5), PolarComplex::new(3.0, 0.5 - f64::consts::PI), PolarComplex::new(3.0, 0.5 + f64::consts::PI))
+    )]
+    fn polar_normalised(p: PolarComplex, expected: PolarComplex, expected_positive: PolarComplex) {
+        let result = p.normalised();
+        assert_eq!(expected.r, result.r);
+        assert_close!(expected.theta, result.theta, 1e-14);
+
+        let result = p.normalised_positive();
+        assert_eq!(expected_positive.r, result.r);
+        assert_close!(expected_positive.theta, result.theta, 1e-14);
+    }
+
+    #[rstest(
+        p, unit, expected,
+        case::radians(PolarComplex::new(2.0, 0.5), AngleUnit::Radians, "@{2, 0.5}"),
+        case::degrees(PolarComplex::new(2.0, f64::consts::FRAC_PI_4), AngleUnit::Degrees, "@{2, 45deg}"),
+        case::gradians(PolarComplex::new(1.5, -f64::consts::PI), AngleUnit::Gradians, "@{1.5, -200grad}")
+    )]
+    fn polar_string_in_unit(p: PolarComplex, unit: AngleUnit, expected: &str) {
+        crate::angle::set_angle_unit(unit);
+        let result = p.to_string();
+        crate::angle::set_angle_unit(AngleUnit::Radians);
+
         assert_eq!(expected, result);
     }
+
+    #[rstest(
+        p, expected,
+        case::right_angle(PolarComplex::from_degrees(10.0, 90.0), Complex::new(0.0, 10.0)),
+        case::half_turn(PolarComplex::from_degrees(2.0, 180.0), Complex::new(-2.0, 0.0)),
+        case::minus_right_angle(PolarComplex::from_gradians(3.0, -100.0), Complex::new(0.0, -3.0))
+    )]
+    fn polar_on_axis_is_exact(p: PolarComplex, expected: Complex) {
+        assert_eq!(expected, p.to_complex());
+    }
+
+    #[test]
+    fn polar_arithmetic_matches_complex() {
+        for a in BASES {
+            for b in BASES {
+                let (pa, pb) = (a.to_polar(), b.to_polar());
+
+                assert!(relatively_close(a * b, (pa * pb).to_complex()), "{a} * {b}");
+                assert!(relatively_close(a / b, (pa / pb).to_complex()), "{a} / {b}");
+            }
+            for p in [-2.5, -1.0, 0.5, 3.0] {
+                assert!(relatively_close(a.pow(p).unwrap(), a.to_polar().pow(p).to_complex()), "{a}^{p}");
+            }
+        }
+    }
+
+    // Going round through polar form loses no more than a few ulps relative
+    // to the modulus, and the polar form comes back normalised.
+    #[test]
+    fn polar_round_trip() {
+        let ulps = |x: Complex, y: Complex| (x - y).to_polar().r / (f64::EPSILON * x.to_polar().r);
+
+        for re in [-1e200, -7.5, -1.0, -1e-3, 0.0, 2e-9, 0.3, 1.0, 42.0, 6e150] {
+            for im in [-3e100, -2.0, -0.0, 1e-5, 0.7, 1.0, 9e9] {
+                let z = Complex::new(re, im);
+                if z == Complex::ZERO {
+                    continue;
+                }
+                assert!(ulps(z, z.to_polar().to_complex()) <= 4.0, "{z:?}");
+                assert!(ulps(z, Complex::from(PolarComplex::from(z))) <= 4.0, "{z:?}");
+            }
+        }
+
+        for r in [1e-100, 0.5, 1.0, 3.0, 1e100] {
+            for theta in [-3.0, -1.0, -0.1, 0.0, 0.25, 1.5, 3.1] {
+                let p = PolarComplex::new(r, theta).to_complex().to_polar();
+                assert!((p.r - r).abs() <= 4.0 * f64::EPSILON * r, "r = {r}, theta = {theta}");
+                assert_close!(theta, p.theta, 4.0 * f64::EPSILON * theta.abs().max(1.0));
+            }
+        }
+    }
+
+    #[rstest(
+        c, abs, arg, sign,
+        case::zero(Complex::new(0.0, 0.0), 0.0, 0.0, Complex::new(0.0, 0.0)),
+        case::positive(Complex::new(3.0, 4.0), 5.0, 0.9272952180016122, Complex::new(0.6, 0.8)),
+        case::negative_real(Complex::new(-2.0, 0.0), 2.0, f64::consts::PI, Complex::new(-1.0, 0.0)),
+        case::below_cut(Complex::new(-2.0, -0.0), 2.0, -f64::consts::PI, Complex::new(-1.0, -0.0)),
+        case::huge(Complex::new(0.0, -1e308), 1e308, -f64::consts::FRAC_PI_2, Complex::new(0.0, -1.0))
+    )]
+    fn modulus_argument_sign(c: Complex, abs: f64, arg: f64, sign: Complex) {
+        assert_eq!(abs, c.abs());
+        assert_eq!(arg, c.arg());
+        assert_eq!(sign, c.sign());
+    }
+
+    #[rstest(
+        c, expected,
+        case::huge(Complex::new(3e300, 4e300), 5e300),
+        case::tiny(Complex::new(3e-320, 4e-320), 5e-320),
+        case::mixed(Complex::new(f64::MAX, 1.0), f64::MAX)
+    )]
+    fn to_polar_scales(c: Complex, expected: f64) {
+        assert_eq!(expected, c.to_polar().r);
+    }
+
+    #[rstest(
+        a, b, expected,
+        case::div(Complex::new(3.0, 1.0), Complex::new(2.0, -1.0), Ok(Complex::new(1.0, 1.0))),
+        case::div_overflow(Complex::new(f64::MAX, f64::MAX), Complex::new(0.5, 0.0), Err(ComplexError::Overflow)),
+        case::div_by_zero(Complex::new(1.1, 1.0), Complex::ZERO, Err(ComplexError::DivisionByZero)),
+        case::div_by_infinity(Complex::new(1.0, 1.0), Complex::new(2.0, f64::INFINITY), Ok(Complex::ZERO)),
+        case::div_infinity_err(Complex::new(f64::INFINITY, 1.0), Complex::new(2.0, f64::INFINITY), Err(ComplexError::NaNProduced)),
+        case::div_nan_err(Complex::new(f64::NAN, 1.0), Complex::new(2.0, -1.0), Err(ComplexError::NaNProduced))
+    )]
+    fn checked_div(a: Complex, b: Complex, expected: Result<Complex, ComplexError>) {
+        let result = a.checked_div(b);
+        assert_eq!(expected, result);
+    }
+
+    #[rstest(
+        a, b, expected,
+        case::add(Complex::new(1.0, 1.0), Complex::new(2.0, -1.0), Complex::new(3.0, 0.0)),
+        case::add_infinity(Complex::new(1.0, 1.0), Complex::new(2.0, f64::INFINITY), Complex::new(3.0, f64::INFINITY))
+    )]
+    fn add_op(a: Complex, b: Complex, expected: Complex) {
+        assert_eq!(expected, a + b);
+    }
+
+    #[rstest(
+        a, b, expected,
+        case::sub(Complex::new(1.0, 1.0), Complex::new(2.0, -1.0), Complex::new(-1.0, 2.0)),
+        case::sub_infinity(Complex::new(1.0, 1.0), Complex::new(2.0, f64::INFINITY), Complex::new(-1.0, f64::NEG_INFINITY))
+    )]
+    fn sub_op(a: Complex, b: Complex, expected: Complex) {
+        assert_eq!(expected, a - b);
+    }
+
+    #[rstest(
+        a, b, expected,
+        case::mul(Complex::new(1.0, 1.0), Complex::new(2.0, -1.0), Complex::new(3.0, 1.0)),
+        case::mul_i_i(Complex::I, Complex::I, Complex::new(-1.0, 0.0))
+    )]
+    fn mul_op(a: Complex, b: Complex, expected: Complex) {
+        assert_eq!(expected, a * b);
+    }
+
+    #[rstest(
+        a, b, expected,
+        case::div(Complex::new(3.0, 1.0), Complex::new(2.0, -1.0), Complex::new(1.0, 1.0)),
+        case::div_one(Complex::new(3.0, 1.0), Complex::ONE, Complex::new(3.0, 1.0))
+    )]
+    fn div_op(a: Complex, b: Complex, expected: Complex) {
+        assert_eq!(expected, a / b);
+    }
+
+    #[test]
+    fn div_op_by_zero_is_not_finite() {
+        let result = Complex64::ONE / Complex::ZERO;
+        assert!(!result.re.is_finite());
+    }
+
+    #[test]
+    fn neg_op() {
+        assert_eq!(Complex::new(-1.0, 2.0), -Complex::new(1.0, -2.0));
+    }
+
+    #[test]
+    fn ops_compose() {
+        let a = Complex::new(1.0, 1.0);
+        let b = Complex::new(2.0, -1.0);
+        let c = Complex::new(0.5, 0.5);
+        assert_eq!(Complex::new(3.5, 1.5), a * b + c);
+    }
+
+    #[rstest(
+        c, f, sum, difference, product, quotient,
+        case::simple(Complex::new(2.0, 4.0), 2.0, Complex::new(4.0, 4.0), Complex::new(0.0, 4.0), Complex::new(4.0, 8.0), Complex::new(1.0, 2.0))
+    )]
+    fn complex_op_f64(c: Complex, f: f64, sum: Complex, difference: Complex, product: Complex, quotient: Complex) {
+        assert_eq!(sum, c + f);
+        assert_eq!(difference, c - f);
+        assert_eq!(product, c * f);
+        assert_eq!(quotient, c / f);
+    }
+
+    #[rstest(
+        f, c, sum, difference, product, quotient,
+        case::simple(2.0, Complex::new(1.0, 1.0), Complex::new(3.0, 1.0), Complex::new(1.0, -1.0), Complex::new(2.0, 2.0), Complex::new(1.0, -1.0))
+    )]
+    fn f64_op_complex(f: f64, c: Complex, sum: Complex, difference: Complex, product: Complex, quotient: Complex) {
+        assert_eq!(sum, f + c);
+        assert_eq!(difference, f - c);
+        assert_eq!(product, f * c);
+        assert_eq!(quotient, f / c);
+    }
+
+    #[test]
+    fn assign_ops() {
+        let mut c = Complex::new(1.0, 1.0);
+        c += Complex::new(1.0, -1.0);
+        assert_eq!(Complex::new(2.0, 0.0), c);
+        c -= 1.0;
+        assert_eq!(Complex::new(1.0, 0.0), c);
+        c *= Complex::I;
+        assert_eq!(Complex::new(0.0, 1.0), c);
+        c /= 2.0;
+        assert_eq!(Complex::new(0.0, 0.5), c);
+        c += 1.0;
+        c -= Complex::I;
+        c *= 4.0;
+        assert_eq!(Complex::new(4.0, -2.0), c);
+        c /= Complex::new(2.0, -1.0);
+        assert_eq!(Complex::new(2.0, 0.0), c);
+    }
+
+    #[rstest(
+        z, expected,
+        case::zero(Complex::ZERO, Complex::ONE),
+        case::one(Complex::ONE, Complex::new(f64::consts::E, 0.0)),
+        case::i_pi(Complex::new(0.0, f64::consts::PI), Complex::new(-1.0, 0.0)),
+        case::general(Complex::new(1.0, 2.0), Complex::new(-1.131204, 2.471727))
+    )]
+    fn exp(z: Complex, expected: Complex) {
+        let result = z.exp().unwrap();
+        assert_complex_close!(expected, result, 0.000001);
+    }
+
+    #[test]
+    fn exp_overflow_err() {
+        assert_eq!(Err(ComplexError::Overflow), Complex::new(1000.0, 0.0).exp());
+    }
+
+    #[rstest(
+        z, expected,
+        case::one(Complex::ONE, Complex::ZERO),
+        case::e(Complex::new(f64::consts::E, 0.0), Complex::ONE),
+        case::i(Complex::I, Complex::new(0.0, f64::consts::FRAC_PI_2)),
+        case::minus_one(Complex::new(-1.0, 0.0), Complex::new(0.0, f64::consts::PI)),
+        case::minus_one_below_cut(Complex::new(-1.0, -0.0), Complex::new(0.0, -f64::consts::PI)),
+        case::general(Complex::new(1.0, 2.0), Complex::new(0.804719, 1.107149))
+    )]
+    fn ln(z: Complex, expected: Complex) {
+        let result = z.ln().unwrap();
+        assert_complex_close!(expected, result, 0.000001);
+    }
+
+    #[test]
+    fn ln_zero_err() {
+        assert_eq!(Err(ComplexError::DivisionByZero), Complex64::ZERO.ln());
+    }
+
+    #[rstest(
+        z, expected,
+        case::hundred(Complex::new(100.0, 0.0), Complex::new(2.0, 0.0)),
+        case::i(Complex::I, Complex::new(0.0, 0.682188))
+    )]
+    fn log10(z: Complex, expected: Complex) {
+        let result = z.log10().unwrap();
+        assert_complex_close!(expected, result, 0.000001);
+    }
+
+    #[rstest(
+        z, base, expected,
+        case::eight_base_two(Complex::new(8.0, 0.0), 2.0, Complex::new(3.0, 0.0)),
+        case::minus_eight_base_two(Complex::new(-8.0, 0.0), 2.0, Complex::new(3.0, 4.532360))
+    )]
+    fn log(z: Complex, base: f64, expected: Complex) {
+        let result = z.log(base).unwrap();
+        assert_complex_close!(expected, result, 0.000001);
+    }
+
+    #[rstest(
+        base,
+        case::zero(0.0),
+        case::one(1.0),
+        case::negative(-2.0)
+    )]
+    fn log_bad_base_err(base: f64) {
+        let result = Complex::new(2.0, 1.0).log(base);
+        assert!(matches!(result, Err(ComplexError::InvalidArgument(_))));
+    }
+
+    #[rstest(
+        z, expected,
+        case::zero(Complex::ZERO, Complex::ZERO),
+        case::four(Complex::new(4.0, 0.0), Complex::new(2.0, 0.0)),
+        case::minus_four(Complex::new(-4.0, 0.0), Complex::new(0.0, 2.0)),
+        case::minus_four_below_cut(Complex::new(-4.0, -0.0), Complex::new(0.0, -2.0)),
+        case::i(Complex::I, Complex::new(f64::consts::FRAC_1_SQRT_2, f64::consts::FRAC_1_SQRT_2)),
+        case::three_four(Complex::new(-3.0, 4.0), Complex::new(1.0, 2.0)),
+        case::three_minus_four(Complex::new(-3.0, -4.0), Complex::new(1.0, -2.0))
+    )]
+    fn sqrt(z: Complex, expected: Complex) {
+        let result = z.sqrt().unwrap();
+        assert_complex_close!(expected, result, 0.000001);
+    }
+
+    #[rstest(
+        z, expected,
+        case::zero(Complex::ZERO, Complex::ZERO),
+        case::eight(Complex::new(8.0, 0.0), Complex::new(2.0, 0.0)),
+        case::minus_eight(Complex::new(-8.0, 0.0), Complex::new(1.0, 1.732051)),
+        case::minus_i(Complex::MINUS_I, Complex::new(0.866025, -0.5))
+    )]
+    fn cbrt(z: Complex, expected: Complex) {
+        let result = z.cbrt().unwrap();
+        assert_complex_close!(expected, result, 0.000001);
+    }
+
+    #[rstest(
+        z, n, expected,
+        case::none(Complex::new(4.0, 0.0), 0, vec![]),
+        case::one(Complex::new(4.0, 1.0), 1, vec![Complex::new(4.0, 1.0)]),
+        case::square(Complex::new(4.0, 0.0), 2, vec![Complex::new(2.0, 0.0), Complex::new(-2.0, 0.0)]),
+        case::square_minus_one(Complex::new(-1.0, 0.0), 2, vec![Complex::I, Complex::MINUS_I]),
+        case::cube(Complex::new(8.0, 0.0), 3, vec![Complex::new(2.0, 0.0), Complex::new(-1.0, 1.732051), Complex::new(-1.0, -1.732051)]),
+        case::fourth_of_i(Complex::I, 4, vec![
+            Complex::new(0.923880, 0.382683),
+            Complex::new(-0.382683, 0.923880),
+            Complex::new(-0.923880, -0.382683),
+            Complex::new(0.382683, -0.923880)])
+    )]
+    fn roots(z: Complex, n: u32, expected: Vec<Complex>) {
+        let result: Vec<Complex> = z.roots(n).collect();
+        assert_eq!(expected.len(), result.len());
+        for (e, r) in expected.iter().zip(result.iter()) {
+            assert_complex_close!(e, r, 0.000001);
+        }
+    }
+
+    #[rstest(n, case::one(1), case::two(2), case::five(5), case::twelve(12))]
+    fn roots_of_unity(n: u32) {
+        let result: Vec<Complex> = Complex::roots_of_unity(n).collect();
+        assert_eq!(n as usize, result.len());
+        assert_complex_close!(Complex64::ONE, result[0], 0.0);
+        for r in result {
+            assert_complex_close!(Complex64::ONE, r.pow(n as f64).unwrap(), 0.000000001);
+        }
+    }
+
+    #[rstest(
+        a, b, sum, product,
+        case::simple(Complex::new(1, 2), Complex::new(3, -1), "{4 + i}", "{5 + 5i}"),
+        case::unit(Complex::new(0, 1), Complex::new(0, 1), "{2i}", "{-1}")
+    )]
+    fn integer_parts(a: Complex<i64>, b: Complex<i64>, sum: &str, product: &str) {
+        assert_eq!(sum, (a + b).to_string());
+        assert_eq!(product, (a * b).to_string());
+    }
+
+    #[test]
+    fn integer_checked_mul_overflow_err() {
+        let a = Complex::new(i32::MAX, 1);
+        assert_eq!(Err(ComplexError::Overflow), a.checked_mul(Complex::new(2, 0)));
+    }
+
+    #[rstest(
+        c, expected,
+        case::tenth(Complex::new(0.1_f32, -0.2_f32), "{0.1 - 0.2i}"),
+        case::big(Complex::new(1.0e20_f32, 1.0_f32), "{1e20 + i}")
+    )]
+    fn f32_string(c: Complex32, expected: &str) {
+        assert_eq!(expected, c.to_string());
+    }
+
+    #[test]
+    fn f32_ops() {
+        let a = Complex32::new(3.0, 1.0);
+        let b = Complex32::new(2.0, -1.0);
+        assert_eq!(Complex32::new(1.0, 1.0), a / b);
+        assert_eq!(Complex32::new(6.0, 2.0), 2.0 * a);
+    }
 }
@@ -0,0 +1,193 @@
+use std::fmt;
+
+use crate::complex::{ArithmeticMode, Complex};
+use crate::error::ComplexError;
+use crate::functions;
+use crate::gaussian::{self, GaussianInt};
+use crate::functions::arity_error;
+use crate::num::{Float, Num};
+use crate::parse_complex::{from_rectangular_str, parse};
+use crate::rational::{Rational, RationalComplex};
+use crate::value::Value;
+
+/// A number system that the shell can evaluate expressions in. Each binary
+/// operator maps to one of the methods here, and function calls are looked up
+/// through the domain so that each can offer its own set of functions. The
+/// arithmetic mode only matters to domains that have infinities and NaNs.
+pub trait Domain: Clone + fmt::Debug {
+    /// Parse a number literal, e.g. `{1 + 2i}`.
+    fn parse_literal(s: &str) -> Result<Self, ComplexError>;
+
+    /// `+`
+    fn plus(&self, other: &Self, mode: ArithmeticMode) -> Result<Self, ComplexError>;
+    /// `-`
+    fn minus(&self, other: &Self, mode: ArithmeticMode) -> Result<Self, ComplexError>;
+    /// `*`
+    fn times(&self, other: &Self, mode: ArithmeticMode) -> Result<Self, ComplexError>;
+    /// `/`
+    fn divide(&self, other: &Self, mode: ArithmeticMode) -> Result<Self, ComplexError>;
+    /// `^`
+    fn power(&self, other: &Self, mode: ArithmeticMode) -> Result<Self, ComplexError>;
+    /// Unary `-`
+    fn negate(&self, mode: ArithmeticMode) -> Result<Self, ComplexError>;
+
+    /// Call the named function on arguments that have already been evaluated.
+    fn call(name: &str, args: &[Self], mode: ArithmeticMode) -> Result<Value<Self>, ComplexError>;
+
+    fn to_std_string(&self, magnitude: usize, precision: usize) -> String;
+
+    /// Format as a literal that parses back to exactly this number.
+    fn to_literal_string(&self) -> String;
+}
+
+/// Complex numbers with floating point parts, which with `f64` parts are the
+/// default domain, and with `BigFloat` parts the arbitrary precision one.
+impl<T: Float> Domain for Complex<T> {
+    fn parse_literal(s: &str) -> Result<Self, ComplexError> {
+        parse(s)
+    }
+
+    fn plus(&self, other: &Self, mode: ArithmeticMode) -> Result<Self, ComplexError> {
+        self.add_in(*other, mode)
+    }
+
+    fn minus(&self, other: &Self, mode: ArithmeticMode) -> Result<Self, ComplexError> {
+        self.sub_in(*other, mode)
+    }
+
+    fn times(&self, other: &Self, mode: ArithmeticMode) -> Result<Self, ComplexError> {
+        self.mul_in(*other, mode)
+    }
+
+    fn divide(&self, other: &Self, mode: ArithmeticMode) -> Result<Self, ComplexError> {
+        self.div_in(*other, mode)
+    }
+
+    fn power(&self, other: &Self, mode: ArithmeticMode) -> Result<Self, ComplexError> {
+        self.pow_in(*other, mode)
+    }
+
+    fn negate(&self, mode: ArithmeticMode) -> Result<Self, ComplexError> {
+        match mode {
+            // 0 - z rather than -z, so that -{4} is -4 + 0i like {-4}
+            ArithmeticMode::Strict => Complex::ZERO.checked_sub(*self),
+            // -z flips the signs of zeros and negates infinities as IEEE 754 does
+            ArithmeticMode::Ieee => Ok(-*self),
+        }
+    }
+
+    fn call(name: &str, args: &[Self], mode: ArithmeticMode) -> Result<Value<Self>, ComplexError> {
+        functions::call(name, args, mode)
+    }
+
+    fn to_std_string(&self, magnitude: usize, precision: usize) -> String {
+        Complex::to_std_string(self, magnitude, precision)
+    }
+
+    fn to_literal_string(&self) -> String {
+        Complex::to_literal_string(self)
+    }
+}
+
+/// Gaussian integers, for the shell's exact mode. Division must come out
+/// exactly and powers must be whole numbers; `divrem` gives the quotient and
+/// remainder of a division that doesn't.
+impl Domain for GaussianInt {
+    fn parse_literal(s: &str) -> Result<Self, ComplexError> {
+        from_rectangular_str(s)
+    }
+
+    fn plus(&self, other: &Self, _mode: ArithmeticMode) -> Result<Self, ComplexError> {
+        self.checked_add(*other)
+    }
+
+    fn minus(&self, other: &Self, _mode: ArithmeticMode) -> Result<Self, ComplexError> {
+        self.checked_sub(*other)
+    }
+
+    fn times(&self, other: &Self, _mode: ArithmeticMode) -> Result<Self, ComplexError> {
+        self.checked_mul(*other)
+    }
+
+    fn divide(&self, other: &Self, _mode: ArithmeticMode) -> Result<Self, ComplexError> {
+        match self.div_rem(*other)? {
+            (q, GaussianInt::ZERO) => Ok(q),
+            _ => Err(ComplexError::InvalidArgument(format!("{self} is not divisible by {other}"))),
+        }
+    }
+
+    fn power(&self, other: &Self, _mode: ArithmeticMode) -> Result<Self, ComplexError> {
+        match u32::try_from(other.re) {
+            Ok(n) if other.im == 0 => self.checked_pow(n),
+            _ => Err(ComplexError::InvalidArgument(format!("{other} is not a whole number power"))),
+        }
+    }
+
+    fn negate(&self, _mode: ArithmeticMode) -> Result<Self, ComplexError> {
+        Complex::ZERO.checked_sub(*self)
+    }
+
+    fn call(name: &str, args: &[Self], _mode: ArithmeticMode) -> Result<Value<Self>, ComplexError> {
+        gaussian::call(name, args)
+    }
+
+    fn to_std_string(&self, magnitude: usize, precision: usize) -> String {
+        Complex::to_std_string(self, magnitude, precision)
+    }
+
+    fn to_literal_string(&self) -> String {
+        Complex::to_literal_string(self)
+    }
+}
+
+/// Complex numbers with exact rational parts, e.g. `{1/3 + 2/7i}`. Powers
+/// must be whole numbers, which may be negative.
+impl Domain for RationalComplex {
+    fn parse_literal(s: &str) -> Result<Self, ComplexError> {
+        from_rectangular_str(s)
+    }
+
+    fn plus(&self, other: &Self, _mode: ArithmeticMode) -> Result<Self, ComplexError> {
+        self.checked_add(*other)
+    }
+
+    fn minus(&self, other: &Self, _mode: ArithmeticMode) -> Result<Self, ComplexError> {
+        self.checked_sub(*other)
+    }
+
+    fn times(&self, other: &Self, _mode: ArithmeticMode) -> Result<Self, ComplexError> {
+        self.checked_mul(*other)
+    }
+
+    fn divide(&self, other: &Self, _mode: ArithmeticMode) -> Result<Self, ComplexError> {
+        self.checked_div(*other)
+    }
+
+    fn power(&self, other: &Self, _mode: ArithmeticMode) -> Result<Self, ComplexError> {
+        match i32::try_from(other.re.numerator()) {
+            Ok(n) if other.re.is_integer() && other.im == Rational::ZERO => self.checked_powi(n),
+            _ => Err(ComplexError::InvalidArgument(format!("{other} is not a whole number power"))),
+        }
+    }
+
+    fn negate(&self, _mode: ArithmeticMode) -> Result<Self, ComplexError> {
+        Complex::ZERO.checked_sub(*self)
+    }
+
+    fn call(name: &str, args: &[Self], _mode: ArithmeticMode) -> Result<Value<Self>, ComplexError> {
+        match (name, args) {
+            ("conj", [z]) => Ok(Value::Number(Complex::new(z.re, Rational::ZERO.checked_sub(z.im)?))),
+            ("norm", [z]) => Ok(Value::Number(Complex::new(z.checked_norm()?, Rational::ZERO))),
+            ("conj" | "norm", _) => Err(arity_error(name, 1, args)),
+            _ => Err(ComplexError::UnknownFunction(name.to_owned())),
+        }
+    }
+
+    fn to_std_string(&self, magnitude: usize, precision: usize) -> String {
+        Complex::to_std_string(self, magnitude, precision)
+    }
+
+    fn to_literal_string(&self) -> String {
+        Complex::to_literal_string(self)
+    }
+}
@@ -0,0 +1,236 @@
+use crate::complex::Complex;
+use crate::error::ComplexError;
+use crate::functions::arity_error;
+use crate::num::Float;
+use crate::value::Value;
+
+// Impedances are complex numbers in ohms, and voltages and currents are
+// phasors in volts and amps. Frequencies are in hertz, so ω = 2πf.
+
+/// The impedance of a resistance of `r` ohms, which is `r` at any frequency.
+pub fn resistor<T: Float>(r: T) -> Result<Complex<T>, ComplexError> {
+    Ok(Complex::new(non_negative("resistance", r)?, T::ZERO))
+}
+
+/// The impedance of an inductance of `l` henries at `f` hertz, `jωL`.
+pub fn inductor<T: Float>(l: T, f: T) -> Result<Complex<T>, ComplexError> {
+    let l = non_negative("inductance", l)?;
+    let omega = angular_frequency(f)?;
+
+    Complex::new(T::ZERO, omega).checked_mul(Complex::from(l))
+}
+
+/// The impedance of a capacitance of `c` farads at `f` hertz, `1 / jωC`. A
+/// capacitor is an open circuit at DC, so this is a division by zero when
+/// either is zero.
+pub fn capacitor<T: Float>(c: T, f: T) -> Result<Complex<T>, ComplexError> {
+    let c = non_negative("capacitance", c)?;
+    let omega = angular_frequency(f)?;
+
+    Complex::<T>::ONE.checked_div(Complex::new(T::ZERO, omega * c))
+}
+
+/// Impedances in series, which add.
+pub fn series<T: Float>(zs: &[Complex<T>]) -> Result<Complex<T>, ComplexError> {
+    zs.iter().try_fold(Complex::ZERO, |sum, z| sum.checked_add(*z))
+}
+
+/// Impedances in parallel, the reciprocal of the sum of their reciprocals. A
+/// zero impedance shorts out the rest, so makes the whole zero.
+pub fn parallel<T: Float>(zs: &[Complex<T>]) -> Result<Complex<T>, ComplexError> {
+    if zs.contains(&Complex::ZERO) {
+        return Ok(Complex::ZERO);
+    }
+
+    let admittance = zs.iter().try_fold(Complex::ZERO, |sum, z| sum.checked_add(Complex::<T>::ONE.checked_div(*z)?))?;
+
+    Complex::<T>::ONE.checked_div(admittance)
+}
+
+/// The voltage across `z2` when `v` is applied across `z1` and `z2` in series,
+/// `v z2 / (z1 + z2)`.
+pub fn voltage_divider<T: Float>(v: Complex<T>, z1: Complex<T>, z2: Complex<T>) -> Result<Complex<T>, ComplexError> {
+    v.checked_mul(z2)?.checked_div(z1.checked_add(z2)?)
+}
+
+/// The power delivered by an RMS voltage phasor driving an RMS current phasor.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Power<T = f64> {
+    /// P, in watts.
+    pub real: T,
+    /// Q, in volt-amperes reactive, positive for an inductive (lagging) load.
+    pub reactive: T,
+    /// |S|, in volt-amperes.
+    pub apparent: T,
+}
+
+impl<T: Float> Power<T> {
+    /// The complex power `S = V I*`.
+    pub fn new(v: Complex<T>, i: Complex<T>) -> Result<Self, ComplexError> {
+        let s = v.checked_mul(i.conj())?;
+
+        Ok(Power { real: s.re, reactive: s.im, apparent: s.re.hypot(s.im) })
+    }
+
+    /// The power factor `P / |S|`, which is undefined when no power flows.
+    pub fn factor(&self) -> Result<T, ComplexError> {
+        self.real.checked_div(self.apparent)
+    }
+}
+
+/// The RMS phasor of a sinusoid with the given peak phasor, `peak / √2`.
+pub fn rms<T: Float>(peak: Complex<T>) -> Complex<T> {
+    peak / two::<T>().sqrt()
+}
+
+/// The peak phasor of a sinusoid with the given RMS phasor, `rms √2`.
+pub fn peak<T: Float>(rms: Complex<T>) -> Complex<T> {
+    rms * two::<T>().sqrt()
+}
+
+/// The shell's circuit functions: `zr(R)`, `zl(L, f)`, `zc(C, f)`,
+/// `ser(z, ...)`, `par(z, ...)`, `vdiv(v, z1, z2)`, `power(v, i)` giving
+/// `[P, Q, |S|]`, `pf(v, i)`, `rms(z)` and `peak(z)`.
+pub fn call<T: Float>(name: &str, args: &[Complex<T>]) -> Result<Value<Complex<T>>, ComplexError> {
+    let z = match (name, args) {
+        ("zr", [r]) => resistor(real(name, r)?)?,
+        ("zl", [l, f]) => inductor(real(name, l)?, real(name, f)?)?,
+        ("zc", [c, f]) => capacitor(real(name, c)?, real(name, f)?)?,
+        ("ser", _) => series(args)?,
+        ("par", _) => parallel(args)?,
+        ("vdiv", [v, z1, z2]) => voltage_divider(*v, *z1, *z2)?,
+        ("power", [v, i]) => {
+            let p = Power::new(*v, *i)?;
+            return Ok(Value::Vector(vec![p.real.into(), p.reactive.into(), p.apparent.into()]));
+        }
+        ("pf", [v, i]) => Power::new(*v, *i)?.factor()?.into(),
+        ("rms", [z]) => rms(*z),
+        ("peak", [z]) => peak(*z),
+        ("zr" | "rms" | "peak", _) => return Err(arity_error(name, 1, args)),
+        ("zl" | "zc" | "power" | "pf", _) => return Err(arity_error(name, 2, args)),
+        ("vdiv", _) => return Err(arity_error(name, 3, args)),
+        _ => return Err(ComplexError::UnknownFunction(name.to_owned())),
+    };
+
+    Ok(Value::Number(z))
+}
+
+fn two<T: Float>() -> T {
+    T::ONE + T::ONE
+}
+
+fn angular_frequency<T: Float>(f: T) -> Result<T, ComplexError> {
+    Ok(two::<T>() * T::pi() * non_negative("frequency", f)?)
+}
+
+fn non_negative<T: Float>(quantity: &str, x: T) -> Result<T, ComplexError> {
+    if x >= T::ZERO && x.is_finite() {
+        Ok(x)
+    } else {
+        Err(ComplexError::InvalidArgument(format!("{quantity} must not be negative but was {x}")))
+    }
+}
+
+// Interpret an argument as a real quantity.
+fn real<T: Float>(name: &str, c: &Complex<T>) -> Result<T, ComplexError> {
+    if c.im == T::ZERO {
+        Ok(c.re)
+    } else {
+        Err(ComplexError::InvalidArgument(format!("{name} needs a real number but was given {c}")))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    fn close(x: Complex, y: Complex) -> bool {
+        (x - y).to_polar().r() <= 1e-9 * y.to_polar().r().max(1.0)
+    }
+
+    #[rstest(
+        z, expected,
+        case::resistor(resistor(50.0), Complex::new(50.0, 0.0)),
+        case::inductor(inductor(1e-3, 60.0), Complex::new(0.0, 0.37699111843077515)),
+        case::inductor_dc(inductor(1.0, 0.0), Complex::ZERO),
+        case::capacitor(capacitor(100e-6, 50.0), Complex::new(0.0, -31.830988618379067)),
+        case::series_rlc(series(&[resistor(10.0).unwrap(), inductor(0.1, 50.0).unwrap(), capacitor(100e-6, 50.0).unwrap()]),
+            Complex::new(10.0, -0.41506208248113197)),
+        case::parallel_resistors(parallel(&[Complex::new(6.0, 0.0), Complex::new(3.0, 0.0)]), Complex::new(2.0, 0.0)),
+        case::parallel_rc(parallel(&[Complex::new(100.0, 0.0), Complex::new(0.0, -100.0)]), Complex::new(50.0, -50.0)),
+        case::parallel_short(parallel(&[Complex::new(100.0, 0.0), Complex::ZERO]), Complex::ZERO),
+        case::divider(voltage_divider(Complex::new(10.0, 0.0), Complex::new(1000.0, 0.0), Complex::new(0.0, -1000.0)),
+            Complex::new(5.0, -5.0))
+    )]
+    fn impedance(z: Result<Complex, ComplexError>, expected: Complex) {
+        let z = z.unwrap();
+        assert!(close(z, expected), "{z} != {expected}");
+    }
+
+    #[rstest(
+        z, expected,
+        case::negative_resistor(resistor(-1.0), ComplexError::InvalidArgument("resistance must not be negative but was -1".to_owned())),
+        case::negative_frequency(inductor(1.0, -60.0), ComplexError::InvalidArgument("frequency must not be negative but was -60".to_owned())),
+        case::capacitor_dc(capacitor(1e-6, 0.0), ComplexError::DivisionByZero),
+        case::no_capacitance(capacitor(0.0, 60.0), ComplexError::DivisionByZero),
+        // an ideal parallel LC circuit at resonance has infinite impedance
+        case::parallel_resonant(parallel(&[Complex::new(0.0, 5.0), Complex::new(0.0, -5.0)]), ComplexError::DivisionByZero)
+    )]
+    fn impedance_fails(z: Result<Complex, ComplexError>, expected: ComplexError) {
+        assert_eq!(Err(expected), z);
+    }
+
+    #[rstest(
+        v, i, real, reactive, apparent, factor,
+        case::resistive(Complex::new(120.0, 0.0), Complex::new(2.0, 0.0), 240.0, 0.0, 240.0, 1.0),
+        case::inductive(Complex::new(230.0, 0.0), Complex::new(8.0, -6.0), 1840.0, 1380.0, 2300.0, 0.8),
+        case::capacitive(Complex::new(0.0, 100.0), Complex::new(-3.0, 4.0), 400.0, -300.0, 500.0, 0.8),
+        case::leading(Complex::new(100.0, 0.0), Complex::new(0.0, 1.0), 0.0, -100.0, 100.0, 0.0)
+    )]
+    fn power(v: Complex, i: Complex, real: f64, reactive: f64, apparent: f64, factor: f64) {
+        let p = Power::new(v, i).unwrap();
+        assert_eq!(Power { real, reactive, apparent }, p);
+        assert_eq!(Ok(factor), p.factor());
+    }
+
+    #[test]
+    fn no_power_factor() {
+        assert_eq!(Err(ComplexError::NaNProduced), Power::new(Complex::new(5.0, 0.0), Complex::ZERO).unwrap().factor());
+    }
+
+    #[test]
+    fn rms_round_trip() {
+        let v = Complex::new(325.0, -17.5);
+        assert!(close(Complex::new(229.8097039, -12.3743687), rms(v)));
+        assert!(close(v, peak(rms(v))));
+    }
+
+    #[rstest(
+        name, args, expected,
+        case::zr("zr", vec![Complex::new(50.0, 0.0)], "{50}"),
+        case::zl("zl", vec![Complex::new(0.5, 0.0), Complex::new(50.0, 0.0)], "{157.079633i}"),
+        case::zc("zc", vec![Complex::new(1e-6, 0.0), Complex::new(1000.0, 0.0)], "{-159.154943i}"),
+        case::ser("ser", vec![Complex::new(3.0, 0.0), Complex::new(0.0, 4.0), Complex::new(1.0, -1.0)], "{4 + 3i}"),
+        case::par("par", vec![Complex::new(2.0, 0.0), Complex::new(2.0, 0.0), Complex::new(1.0, 0.0)], "{0.5}"),
+        case::vdiv("vdiv", vec![Complex::new(12.0, 0.0), Complex::new(2.0, 0.0), Complex::new(1.0, 0.0)], "{4}"),
+        case::power("power", vec![Complex::new(230.0, 0.0), Complex::new(8.0, -6.0)], "[{1840}, {1380}, {2300}]"),
+        case::pf("pf", vec![Complex::new(230.0, 0.0), Complex::new(8.0, -6.0)], "{0.8}"),
+        case::rms("rms", vec![Complex::new(2.0, 0.0)], "{1.414214}"),
+        case::peak("peak", vec![Complex::new(0.0, 1.0)], "{1.414214i}")
+    )]
+    fn call_works(name: &str, args: Vec<Complex>, expected: &str) {
+        assert_eq!(expected, call(name, &args).unwrap().to_string());
+    }
+
+    #[rstest(
+        name, args, expected,
+        case::complex_resistance("zr", vec![Complex::new(1.0, 1.0)], ComplexError::InvalidArgument("zr needs a real number but was given {1 + i}".to_owned())),
+        case::zl_one_arg("zl", vec![Complex::ONE], ComplexError::ArityMismatch { name: "zl".to_owned(), expected: 2, found: 1 }),
+        case::vdiv_two_args("vdiv", vec![Complex::ONE, Complex::ONE], ComplexError::ArityMismatch { name: "vdiv".to_owned(), expected: 3, found: 2 }),
+        case::unknown("zq", vec![Complex::ONE], ComplexError::UnknownFunction("zq".to_owned()))
+    )]
+    fn call_fails(name: &str, args: Vec<Complex>, expected: ComplexError) {
+        assert_eq!(Err(expected), call(name, &args));
+    }
+}
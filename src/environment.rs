@@ -0,0 +1,112 @@
+use std::collections::BTreeMap;
+
+use crate::complex::Complex;
+use crate::expr::Expr;
+use crate::value::Value;
+
+/// A function defined in the shell, such as `f(z) = z^2 + {0.25}`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Function<N = Complex> {
+    pub params: Vec<String>,
+    pub body: Expr<N>,
+}
+
+/// The variables that expressions can refer to, such as `ans` for the last
+/// result in the shell, and the functions defined there.
+#[derive(Clone, Debug)]
+pub struct Environment<N = Complex> {
+    variables: BTreeMap<String, Value<N>>,
+    functions: BTreeMap<String, Function<N>>,
+}
+
+impl<N> Default for Environment<N> {
+    fn default() -> Self {
+        Environment { variables: BTreeMap::new(), functions: BTreeMap::new() }
+    }
+}
+
+impl<N> Environment<N> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn get(&self, name: &str) -> Option<&Value<N>> {
+        self.variables.get(name)
+    }
+
+    pub fn set(&mut self, name: &str, value: Value<N>) {
+        self.variables.insert(name.to_owned(), value);
+    }
+
+    pub fn function(&self, name: &str) -> Option<&Function<N>> {
+        self.functions.get(name)
+    }
+
+    /// Define a function, replacing any of the same name.
+    pub fn define(&mut self, name: &str, function: Function<N>) {
+        self.functions.insert(name.to_owned(), function);
+    }
+
+    /// Forget every variable and function.
+    pub fn clear(&mut self) {
+        self.variables.clear();
+        self.functions.clear();
+    }
+
+    /// The variables in order of name.
+    pub fn variables(&self) -> impl Iterator<Item = (&str, &Value<N>)> {
+        self.variables.iter().map(|(name, value)| (name.as_str(), value))
+    }
+
+    /// The defined functions in order of name.
+    pub fn functions(&self) -> impl Iterator<Item = (&str, &Function<N>)> {
+        self.functions.iter().map(|(name, function)| (name.as_str(), function))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::complex::ArithmeticMode;
+    use crate::error::ComplexError;
+    use crate::expr::Statement;
+    use rstest::rstest;
+
+    #[test]
+    fn set_and_clear() {
+        let mut env: Environment = Environment::new();
+        env.set("z", Value::Number(Complex::I));
+        env.set("a", Value::Vector(vec![Complex::ONE]));
+        env.set("z", Value::Number(Complex::ONE));
+
+        assert_eq!(Some(&Value::Number(Complex::ONE)), env.get("z"));
+        assert_eq!(vec!["a", "z"], env.variables().map(|(name, _)| name).collect::<Vec<_>>());
+
+        env.define("f", Function { params: vec!["z".to_owned()], body: Expr::Variable("z".to_owned()) });
+        assert_eq!(vec!["f"], env.functions().map(|(name, _)| name).collect::<Vec<_>>());
+
+        env.clear();
+        assert_eq!(None, env.get("z"));
+        assert_eq!(None, env.function("f"));
+    }
+
+    #[rstest(
+        input, expected,
+        case::one_parameter("f({2i})", Ok(Value::Number(Complex::new(-3.75, 0.0)))),
+        case::parameter_hides_variable("g({1}, {2})", Ok(Value::Number(Complex::new(3.0, 0.0)))),
+        case::global_variable("h({1})", Ok(Value::Number(Complex::new(11.0, 0.0)))),
+        case::nested("f(g({0}, {1}))", Ok(Value::Number(Complex::new(1.25, 0.0)))),
+        case::lexical_scope("k({5})", Ok(Value::Number(Complex::new(11.0, 0.0)))),
+        case::wrong_arity("f({1}, {2})", Err(ComplexError::ArityMismatch { name: "f".to_owned(), expected: 1, found: 2 })),
+        case::recursive("loop({1})", Err(ComplexError::RecursionLimit("loop".to_owned())))
+    )]
+    fn call(input: &str, expected: Result<Value, ComplexError>) {
+        let mut env: Environment = Environment::new();
+        env.set("z", Value::Number(Complex::new(10.0, 0.0)));
+        for definition in ["f(z) = z^2 + {0.25}", "g(z, w) = z + w", "h(w) = z + w", "k(z) = h({1})", "loop(z) = loop(z) + {1}"] {
+            Statement::parse(definition).unwrap().execute(&mut env, ArithmeticMode::Strict).unwrap();
+        }
+
+        assert_eq!(expected, Expr::parse(input).unwrap().evaluate(&env, ArithmeticMode::Strict));
+    }
+}
@@ -0,0 +1,124 @@
+use std::{error, fmt};
+
+use crate::parse_complex::LiteralFormat;
+use crate::span::Span;
+
+/// Everything that can go wrong when parsing or evaluating complex numbers.
+#[derive(Clone, Debug, PartialEq)]
+pub enum ComplexError {
+    /// A result was too large to represent.
+    Overflow,
+    /// A result was not a number, e.g. from `∞ - ∞`.
+    NaNProduced,
+    /// Division by zero, or a function evaluated at a pole such as `ln(0)`.
+    DivisionByZero,
+    /// An argument is outside the domain a function accepts.
+    InvalidArgument(String),
+    /// Text could not be parsed as a complex number written in the given
+    /// format. The position is the byte offset into the input of the character
+    /// that doesn't fit the format, which is the length of the input if it
+    /// ended too soon.
+    ParseError { input: String, format: LiteralFormat, position: usize },
+    /// An expression is not well formed.
+    SyntaxError(String),
+    /// A function name that the shell doesn't know.
+    UnknownFunction(String),
+    /// A variable that has not been given a value.
+    UnknownVariable(String),
+    /// Calls to a defined function nested too deeply, as it calls itself.
+    RecursionLimit(String),
+    /// A function was called with the wrong number of arguments.
+    ArityMismatch { name: String, expected: usize, found: usize },
+}
+
+impl ComplexError {
+    /// The error, located at the text of the input in the span.
+    pub fn at(self, span: Span) -> LocatedError {
+        LocatedError { error: self, span }
+    }
+}
+
+/// An error in reading a line of input, along with the span of the input that
+/// it is about, for the shell to point at.
+#[derive(Clone, Debug, PartialEq)]
+pub struct LocatedError {
+    pub error: ComplexError,
+    pub span: Span,
+}
+
+impl fmt::Display for LocatedError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.error.fmt(f)
+    }
+}
+
+impl error::Error for LocatedError {}
+
+/// Drops the span, for callers that only report the error.
+impl From<LocatedError> for ComplexError {
+    fn from(e: LocatedError) -> Self {
+        e.error
+    }
+}
+
+impl fmt::Display for ComplexError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ComplexError::Overflow => write!(f, "Result overflowed"),
+            ComplexError::NaNProduced => write!(f, "Result is not a number"),
+            ComplexError::DivisionByZero => write!(f, "Division by zero"),
+            ComplexError::InvalidArgument(s) => write!(f, "Invalid argument: {s}"),
+            ComplexError::ParseError { input, format, position } => {
+                match input[*position..].chars().next() {
+                    Some(c) => write!(f, "Cannot parse '{input}' as {format}: unexpected '{c}' at position {position}"),
+                    None => write!(f, "Cannot parse '{input}' as {format}: unexpected end at position {position}"),
+                }
+            }
+            ComplexError::SyntaxError(s) => write!(f, "Syntax error: {s}"),
+            ComplexError::UnknownFunction(name) => write!(f, "Unknown function '{name}'"),
+            ComplexError::UnknownVariable(name) => write!(f, "Unknown variable '{name}'"),
+            ComplexError::RecursionLimit(name) => write!(f, "Calls to '{name}' nest too deeply, as it calls itself"),
+            ComplexError::ArityMismatch { name, expected, found } => {
+                let plural = if *expected == 1 { "argument" } else { "arguments" };
+                let verb = if *found == 1 { "was" } else { "were" };
+                write!(f, "{name} takes {expected} {plural} but {found} {verb} given")
+            }
+        }
+    }
+}
+
+impl error::Error for ComplexError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    #[rstest(
+        e, expected,
+        case::overflow(ComplexError::Overflow, "Result overflowed"),
+        case::parse(
+            ComplexError::ParseError { input: "{1 + }".to_owned(), format: LiteralFormat::BracketedStandard, position: 5 },
+            "Cannot parse '{1 + }' as bracketed standard form {a + bi}: unexpected '}' at position 5"),
+        case::parse_end(
+            ComplexError::ParseError { input: "@{1".to_owned(), format: LiteralFormat::Polar, position: 3 },
+            "Cannot parse '@{1' as polar form @{r, theta}: unexpected end at position 3"),
+        case::arity_one(
+            ComplexError::ArityMismatch { name: "sin".to_owned(), expected: 1, found: 2 },
+            "sin takes 1 argument but 2 were given"),
+        case::arity_two(
+            ComplexError::ArityMismatch { name: "roots".to_owned(), expected: 2, found: 1 },
+            "roots takes 2 arguments but 1 was given"),
+    )]
+    fn string(e: ComplexError, expected: &str) {
+        assert_eq!(expected, e.to_string());
+    }
+
+    #[test]
+    fn located() {
+        let e = ComplexError::DivisionByZero.at(Span::new(1, 2));
+        assert_eq!("Division by zero", e.to_string());
+        assert_eq!(Span::new(1, 2), e.span);
+        assert_eq!(ComplexError::DivisionByZero, ComplexError::from(e));
+    }
+}
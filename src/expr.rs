@@ -0,0 +1,402 @@
+use std::fmt;
+
+use crate::complex::{ArithmeticMode, Complex};
+use crate::domain::Domain;
+use crate::environment::{Environment, Function};
+use crate::error::{ComplexError, LocatedError};
+use crate::parser;
+use crate::value::Value;
+
+/// A prefix operator.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum UnaryOp {
+    Plus,
+    Minus,
+    Conjugate,
+}
+
+/// An infix operator.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BinaryOp {
+    Add,
+    Subtract,
+    Multiply,
+    Divide,
+    Power,
+}
+
+/// A parsed expression over numbers of the domain `N`.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Expr<N = Complex> {
+    Number(N),
+    Variable(String),
+    Unary(UnaryOp, Box<Expr<N>>),
+    Binary(BinaryOp, Box<Expr<N>>, Box<Expr<N>>),
+    Call(String, Vec<Expr<N>>),
+}
+
+impl UnaryOp {
+    pub fn to_symbol(&self) -> &'static str {
+        match self {
+            UnaryOp::Plus => "+",
+            UnaryOp::Minus => "-",
+            UnaryOp::Conjugate => "~",
+        }
+    }
+}
+
+impl BinaryOp {
+    pub fn to_symbol(&self) -> &'static str {
+        match self {
+            BinaryOp::Add => "+",
+            BinaryOp::Subtract => "-",
+            BinaryOp::Multiply => "*",
+            BinaryOp::Divide => "/",
+            BinaryOp::Power => "^",
+        }
+    }
+}
+
+impl<N: Domain> Expr<N> {
+    /// Parse an expression such as `sin({1 + i}) * -{2}`.
+    pub fn parse(input: &str) -> Result<Self, LocatedError> {
+        parser::parse(input)
+    }
+
+    /// Evaluate the tree, looking variables up in `env`, with operators
+    /// following the given arithmetic mode.
+    pub fn evaluate(&self, env: &Environment<N>, mode: ArithmeticMode) -> Result<Value<N>, ComplexError> {
+        self.evaluate_in(&Scope { env, params: &[], args: &[], depth: 0 }, mode)
+    }
+
+    fn evaluate_in(&self, scope: &Scope<N>, mode: ArithmeticMode) -> Result<Value<N>, ComplexError> {
+        match self {
+            Expr::Number(c) => Ok(Value::Number(c.clone())),
+            Expr::Variable(name) => scope
+                .get(name)
+                .cloned()
+                .ok_or_else(|| ComplexError::UnknownVariable(name.to_owned())),
+            Expr::Unary(op, operand) => {
+                let operand = operand.number(scope, mode)?;
+
+                match op {
+                    UnaryOp::Plus => Ok(Value::Number(operand)),
+                    UnaryOp::Minus => Ok(Value::Number(operand.negate(mode)?)),
+                    UnaryOp::Conjugate => N::call("conj", &[operand], mode),
+                }
+            }
+            Expr::Binary(op, left, right) => {
+                let left = left.number(scope, mode)?;
+                let right = right.number(scope, mode)?;
+
+                let result = match op {
+                    BinaryOp::Add => left.plus(&right, mode),
+                    BinaryOp::Subtract => left.minus(&right, mode),
+                    BinaryOp::Multiply => left.times(&right, mode),
+                    BinaryOp::Divide => left.divide(&right, mode),
+                    BinaryOp::Power => left.power(&right, mode),
+                };
+
+                result.map(Value::Number)
+            }
+            Expr::Call(name, args) => {
+                if let Some(function) = scope.env.function(name) {
+                    let values = args
+                        .iter()
+                        .map(|arg| arg.evaluate_in(scope, mode))
+                        .collect::<Result<Vec<_>, _>>()?;
+
+                    return scope.call(name, function, &values, mode);
+                }
+
+                let values = args
+                    .iter()
+                    .map(|arg| arg.number(scope, mode))
+                    .collect::<Result<Vec<_>, _>>()?;
+
+                N::call(name, &values, mode)
+            }
+        }
+    }
+
+    // Evaluate where only a single number will do, as for an operand.
+    fn number(&self, scope: &Scope<N>, mode: ArithmeticMode) -> Result<N, ComplexError> {
+        match self.evaluate_in(scope, mode)? {
+            Value::Number(c) => Ok(c),
+            Value::Vector(_) => Err(ComplexError::InvalidArgument(format!("'{}' is a vector, not a number", self))),
+        }
+    }
+
+    // How tightly the expression binds when printed, with atoms the tightest.
+    fn precedence(&self) -> u8 {
+        match self {
+            Expr::Binary(BinaryOp::Add | BinaryOp::Subtract, ..) => 1,
+            Expr::Binary(BinaryOp::Multiply | BinaryOp::Divide, ..) => 2,
+            Expr::Unary(UnaryOp::Plus | UnaryOp::Minus, _) => 3,
+            Expr::Binary(BinaryOp::Power, ..) => 4,
+            Expr::Unary(UnaryOp::Conjugate, _) => 5,
+            Expr::Number(_) | Expr::Variable(_) | Expr::Call(..) => 6,
+        }
+    }
+
+    fn is_prefix(&self) -> bool {
+        matches!(self, Expr::Unary(..))
+    }
+
+    fn write_operand(&self, f: &mut fmt::Formatter<'_>, bracketed: bool) -> fmt::Result {
+        if bracketed {
+            write!(f, "(")?;
+            fmt::Display::fmt(self, f)?;
+            write!(f, ")")
+        } else {
+            fmt::Display::fmt(self, f)
+        }
+    }
+}
+
+// How deeply calls to defined functions can nest. As a body has no way to
+// choose not to call itself, deeper nesting only comes from a function
+// defined in terms of itself.
+const MAX_DEPTH: usize = 64;
+
+// Where names are looked up during evaluation: the parameters of the defined
+// function being called, if any, and then the variables of the environment.
+// A body sees only its own parameters and the globals, never its caller's.
+struct Scope<'a, N> {
+    env: &'a Environment<N>,
+    params: &'a [String],
+    args: &'a [Value<N>],
+    // how many calls to defined functions the evaluation is inside
+    depth: usize,
+}
+
+impl<N: Domain> Scope<'_, N> {
+    fn get(&self, name: &str) -> Option<&Value<N>> {
+        match self.params.iter().position(|param| param == name) {
+            Some(i) => Some(&self.args[i]),
+            None => self.env.get(name),
+        }
+    }
+
+    // Evaluate the body of a defined function with its parameters bound to
+    // the arguments.
+    fn call(&self, name: &str, function: &Function<N>, args: &[Value<N>], mode: ArithmeticMode) -> Result<Value<N>, ComplexError> {
+        if args.len() != function.params.len() {
+            return Err(ComplexError::ArityMismatch { name: name.to_owned(), expected: function.params.len(), found: args.len() });
+        }
+
+        if self.depth >= MAX_DEPTH {
+            return Err(ComplexError::RecursionLimit(name.to_owned()));
+        }
+
+        let scope = Scope { env: self.env, params: &function.params, args, depth: self.depth + 1 };
+        function.body.evaluate_in(&scope, mode)
+    }
+}
+
+/// Prints the expression in a canonical form that parses back to the same
+/// tree, with only the brackets that the precedence and grouping of the
+/// operators need. A prefix operator takes everything after it that binds
+/// more tightly, so one never needs brackets on the right.
+impl<N: Domain> fmt::Display for Expr<N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Expr::Number(c) => write!(f, "{}", c.to_literal_string()),
+            Expr::Variable(name) => write!(f, "{}", name),
+            Expr::Unary(op, operand) => {
+                write!(f, "{}", op.to_symbol())?;
+                operand.write_operand(f, !operand.is_prefix() && operand.precedence() < self.precedence())
+            }
+            Expr::Binary(op, left, right) => {
+                let precedence = self.precedence();
+                let power = *op == BinaryOp::Power;
+
+                left.write_operand(f, left.precedence() < precedence || (power && left.precedence() == precedence))?;
+                if power {
+                    write!(f, "^")?;
+                } else {
+                    write!(f, " {} ", op.to_symbol())?;
+                }
+                right.write_operand(
+                    f,
+                    !right.is_prefix() && (right.precedence() < precedence || (!power && right.precedence() == precedence)),
+                )
+            }
+            Expr::Call(name, args) => {
+                write!(f, "{}(", name)?;
+                for (i, arg) in args.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ", ")?;
+                    }
+                    arg.fmt(f)?;
+                }
+                write!(f, ")")
+            }
+        }
+    }
+}
+
+/// A line of input to the shell, which is an expression, the assignment of
+/// one to a variable, as in `z = {1 + i}`, or the definition of a function,
+/// as in `f(z) = z^2 + {0.25}`.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Statement<N = Complex> {
+    Expression(Expr<N>),
+    Assign(String, Expr<N>),
+    Define(String, Vec<String>, Expr<N>),
+}
+
+impl<N: Domain> Statement<N> {
+    pub fn parse(input: &str) -> Result<Self, LocatedError> {
+        parser::parse_statement(input)
+    }
+
+    /// Run the statement, storing its value in `env` as `ans` and, for an
+    /// assignment, under the assigned name too. A definition stores the
+    /// function and has no value.
+    pub fn execute(&self, env: &mut Environment<N>, mode: ArithmeticMode) -> Result<Option<Value<N>>, ComplexError> {
+        let (name, expr) = match self {
+            Statement::Expression(expr) => ("ans", expr),
+            Statement::Assign(name, expr) => (name.as_str(), expr),
+            Statement::Define(name, params, body) => {
+                env.define(name, Function { params: params.clone(), body: body.clone() });
+                return Ok(None);
+            }
+        };
+
+        let value = expr.evaluate(env, mode)?;
+        env.set(name, value.clone());
+        if name != "ans" {
+            env.set("ans", value.clone());
+        }
+
+        Ok(Some(value))
+    }
+}
+
+impl<N: Domain> fmt::Display for Statement<N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Statement::Expression(expr) => expr.fmt(f),
+            Statement::Assign(name, expr) => {
+                write!(f, "{} = ", name)?;
+                expr.fmt(f)
+            }
+            Statement::Define(name, params, body) => {
+                write!(f, "{}({}) = ", name, params.join(", "))?;
+                body.fmt(f)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bigfloat::BigFloat;
+    use rstest::rstest;
+
+    fn number(re: f64, im: f64) -> Box<Expr> {
+        Box::new(Expr::Number(Complex::new(re, im)))
+    }
+
+    #[rstest(
+        input, expected,
+        case::number("{2 + i}", Expr::Number(Complex::new(2.0, 1.0))),
+        case::sum("{1} + {2}", Expr::Binary(BinaryOp::Add, number(1.0, 0.0), number(2.0, 0.0))),
+        case::product_first("{1} + {2} * {3}", Expr::Binary(
+            BinaryOp::Add,
+            number(1.0, 0.0),
+            Box::new(Expr::Binary(BinaryOp::Multiply, number(2.0, 0.0), number(3.0, 0.0)))
+        )),
+        case::negated_power("-{2}^{2}", Expr::Unary(
+            UnaryOp::Minus,
+            Box::new(Expr::Binary(BinaryOp::Power, number(2.0, 0.0), number(2.0, 0.0)))
+        )),
+        case::call("pow({2}, {1} + {1})", Expr::Call("pow".to_owned(), vec![
+            Expr::Number(Complex::new(2.0, 0.0)),
+            Expr::Binary(BinaryOp::Add, number(1.0, 0.0), number(1.0, 0.0)),
+        ]))
+    )]
+    fn parse(input: &str, expected: Expr) {
+        assert_eq!(Ok(expected), Expr::parse(input));
+    }
+
+    #[rstest(
+        input, expected,
+        case::number("{2 + i}", "{2 + i}"),
+        case::spaced("{1}+{2}*{3}", "{1} + {2} * {3}"),
+        case::needed_brackets("({1} + {2}) * {3}", "({1} + {2}) * {3}"),
+        case::redundant_brackets("(({1})) + ({2} * {3})", "{1} + {2} * {3}"),
+        case::right_difference("{1} - ({2} - {3})", "{1} - ({2} - {3})"),
+        case::left_difference("({1} - {2}) + {3}", "{1} - {2} + {3}"),
+        case::left_quotient("({1} / {2}) / {3}", "{1} / {2} / {3}"),
+        case::negated_power("-{2}^{2}", "-{2}^{2}"),
+        case::power_of_negation("(-{2})^{2}", "(-{2})^{2}"),
+        case::nested_power("({2}^{3})^{2}", "({2}^{3})^{2}"),
+        case::power_chain("{2}^({3}^{2})", "{2}^{3}^{2}"),
+        case::power_of_negative("{2}^(-{2})", "{2}^-{2}"),
+        case::subtract_negative("{1} - (-{2})", "{1} - -{2}"),
+        case::conjugate_of_negative("~(-{i})", "~-{i}"),
+        case::conjugate_of_sum("~({1 + i} + {2})", "~({1 + i} + {2})"),
+        case::conjugate_of_power("~({i}^{2})", "~({i}^{2})"),
+        case::double_negation("--{1}", "--{1}"),
+        case::call("sqrt ({2}*{2})", "sqrt({2} * {2})"),
+        case::call_args("roots({-8},{3})", "roots({-8}, {3})"),
+        case::variables("(z+w)*~z", "(z + w) * ~z"),
+        case::long_literal("{1.23456789 - 0.1000000000000001i}", "{1.23456789 - 0.1000000000000001i}"),
+        case::tiny_literal("{1e-20}", "{1e-20}"),
+        case::huge_literal("{1234567890123.5}", "{1.2345678901235e12}")
+    )]
+    fn pretty_print(input: &str, expected: &str) {
+        let expr: Expr = Expr::parse(input).unwrap();
+        assert_eq!(expected, expr.to_string());
+        assert_eq!(Ok(expr), Expr::parse(expected));
+    }
+
+    #[test]
+    fn literal_round_trip() {
+        let input = "{3.14159265358979323846264338327950288419716939937510582097494}";
+        let expr: Expr<Complex<BigFloat>> = Expr::parse(input).unwrap();
+
+        assert_eq!(input, expr.to_string());
+        assert_eq!(Ok(expr.clone()), Expr::parse(&expr.to_string()));
+    }
+
+    #[test]
+    fn statements() {
+        let mut env = Environment::new();
+        let mode = ArithmeticMode::Strict;
+
+        let run = |input: &str, env: &mut Environment| Statement::parse(input).map_err(ComplexError::from).and_then(|s| s.execute(env, mode)).map(Option::unwrap);
+
+        assert_eq!(Ok(Value::Number(Complex::new(1.0, 1.0))), run("z = {1 + i}", &mut env));
+        assert_eq!(Ok(Value::Number(Complex::new(-1.0, 1.0))), run("z^2 / ~z", &mut env));
+        assert_eq!(Ok(Value::Number(Complex::new(0.0, 2.0))), run("ans + z", &mut env));
+        assert_eq!(Some(&Value::Number(Complex::new(0.0, 2.0))), env.get("ans"));
+        assert_eq!(Some(&Value::Number(Complex::new(1.0, 1.0))), env.get("z"));
+        assert_eq!(Ok(Value::Number(Complex::new(1.0, -1.0))), run("z~", &mut env));
+        assert_eq!(Ok(Value::Number(Complex::new(1.0, 1.0))), run("~z~", &mut env));
+        assert_eq!(Err(ComplexError::UnknownVariable("w".to_owned())), run("w + {1}", &mut env));
+        assert_eq!(Ok(Value::Vector(vec![Complex::new(2.0, 0.0), Complex::new(-2.0, 0.0)])), run("r = roots({4}, {2})", &mut env));
+        assert_eq!(
+            Err(ComplexError::InvalidArgument("'r' is a vector, not a number".to_owned())),
+            run("r * {2}", &mut env)
+        );
+
+        let define = Statement::parse("mobius(z, a, b, c, d) = (a*z + b)/(c*z + d)").unwrap();
+        assert_eq!("mobius(z, a, b, c, d) = (a * z + b) / (c * z + d)", define.to_string());
+        assert_eq!(Ok(None), define.execute(&mut env, mode));
+        assert_eq!(Ok(Value::Number(Complex::new(0.0, 1.0))), run("mobius(i, {1}, {0}, {0}, {1})", &mut env));
+        assert_eq!(Ok(Value::Number(Complex::new(0.5, 0.0))), run("mobius(z - i, {1}, {0}, {1}, {1})", &mut env));
+        assert_eq!(Ok(Value::Number(Complex::new(2.0, 0.0))), run("x = {2}", &mut env));
+        assert_eq!(Some(&Value::Number(Complex::new(2.0, 0.0))), env.get("ans"));
+    }
+
+    #[test]
+    fn deep_expression() {
+        let input = vec!["{1}"; 500].join(" + ");
+        let expr: Expr = Expr::parse(&input).unwrap();
+        assert_eq!(Ok(Value::Number(Complex::new(500.0, 0.0))), expr.evaluate(&Environment::new(), ArithmeticMode::Strict));
+    }
+}
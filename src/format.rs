@@ -1,7 +1,19 @@
 
 // note: rust format! does half-up rounding
 
+use crate::num::Float;
+
 pub fn format_f64(f: f64, max_digit_magnitude: usize, max_precision: usize) -> String {
+    format_float(f, max_digit_magnitude, max_precision)
+}
+
+/// `format_f64` for any width of float. The digits shown are those of the
+/// value at its own width, so an `f32` isn't padded out with the noise of its
+/// `f64` conversion.
+pub fn format_float<T: Float>(f: T, max_digit_magnitude: usize, max_precision: usize) -> String {
+    if !f.is_finite() {
+        return f.to_string();
+    }
 
     let magn = digit_magnitude(f);
 
@@ -14,7 +26,7 @@ pub fn format_f64(f: f64, max_digit_magnitude: usize, max_precision: usize) -> S
     // precision so that
     // f = 0.00005, max magn 3, precision 3 doesn't print as 0 but as 5e-5 and
     // f = 0.00005, max magn 5, precision 5 prints as 0.00005
-    if magn <= max_digit_magnitude && !(abs_f < 1.0 && max_precision < magn){
+    if magn <= max_digit_magnitude && !(abs_f < T::ONE && max_precision < magn){
         format!("{:.max_precision$}", f).trim_end_matches('0').trim_end_matches('.').trim().to_string()
     } else {
         let s = format!("{:.max_precision$e}", f).to_string();
@@ -25,6 +37,36 @@ pub fn format_f64(f: f64, max_digit_magnitude: usize, max_precision: usize) -> S
     }
 }
 
+/// The shortest decimal that parses back to exactly `f`, positional for
+/// exponents from -7 to 11 and in exponent form beyond. This is how literals
+/// are printed, so that they read back unchanged.
+pub fn format_round_trip<T: Float>(f: T) -> String {
+    if !f.is_finite() {
+        return f.to_string();
+    }
+
+    let mut s = String::new();
+    let mut places = 0;
+    while places < MAX_ROUND_TRIP_PLACES {
+        s = format!("{f:.places$e}");
+        if T::from_decimal_str(&s) == Some(f) {
+            break;
+        }
+        places += 1;
+    }
+
+    let exponent: i64 = s.split_once('e').and_then(|(_, e)| e.parse().ok()).unwrap_or(0);
+    if (-7..12).contains(&exponent) {
+        let places = (places as i64 - exponent).max(0) as usize;
+        format!("{f:.places$}")
+    } else {
+        s
+    }
+}
+
+// Beyond the digits that any of the float types could need.
+const MAX_ROUND_TRIP_PLACES: usize = 400;
+
 /// Returns the number of digits before the decimal point, with the caveat that the
 /// result for zero is 1, or, if the number is less than zero, the number of zeros
 /// before the first non-zero digit plus 1.
@@ -41,13 +83,17 @@ pub fn format_f64(f: f64, max_digit_magnitude: usize, max_precision: usize) -> S
 /// - 123456789.345678 -> 9
 /// - -0.00000000001 -> 12
 /// 
-fn digit_magnitude(f: f64) -> usize {
-    let neg: u16 = if f < 0.0 { 1 } else { 0 };
+fn digit_magnitude<T: Float>(f: T) -> usize {
+    let neg: u16 = if f < T::ZERO { 1 } else { 0 };
 
-    if f == 0.0 {
+    if f == T::ZERO {
         1
     } else {
-        let m = f.abs().log10().floor() as i16;
+        // f64 has the most accurate log10 of any type that fits it, and the
+        // wider types need their own
+        let g = f.to_f64();
+        let log = if g.is_finite() && g != 0.0 { g.abs().log10() } else { f.abs().log10().to_f64() };
+        let m = log.floor() as i16;
         if m >= 0 {
             let mm = m.cast_unsigned() + 1 + neg;
             mm as usize
@@ -80,13 +126,30 @@ mod tests {
         case::large_few_digits(93000000000.0, 8, 6, "9.3e10"),
         case::below_abs_one_go_exp_to_avoid_trunc_to_zero(0.00005, 3, 3, "5e-5"),
         case::below_abs_one_go_exp_to_avoid_trunc_to_zero(-0.000059, 5, 3, "-5.9e-5"),
-        case::below_abs_one_no_trunc_need(0.00005, 5, 5, "0.00005")
+        case::below_abs_one_no_trunc_need(0.00005, 5, 5, "0.00005"),
+        case::infinity(f64::INFINITY, 6, 3, "inf"),
+        case::neg_infinity(f64::NEG_INFINITY, 6, 3, "-inf"),
+        case::nan(f64::NAN, 6, 3, "NaN")
     )]
     fn string(f: f64, len: usize, precision: usize, expected: &str) {
         let result = format_f64(f, len, precision);
         assert_eq!(expected, result);
     }
 
+    #[rstest(
+        f, expected,
+        case::whole(100000.0, "100000"),
+        case::long(1.23456789, "1.23456789"),
+        case::shortest(0.1 + 0.2, "0.30000000000000004"),
+        case::small(0.000001, "0.000001"),
+        case::tiny(1.5e-8, "1.5e-8"),
+        case::big(-1.5e12, "-1.5e12"),
+        case::infinity(f64::INFINITY, "inf")
+    )]
+    fn round_trip(f: f64, expected: &str) {
+        assert_eq!(expected, format_round_trip(f));
+    }
+
     #[rstest(
         f, expected,
         case::zero(0.0, 1),
@@ -0,0 +1,121 @@
+use crate::complex::{ArithmeticMode, Complex};
+use crate::ee;
+use crate::error::ComplexError;
+use crate::num::Float;
+use crate::value::Value;
+
+/// Call a named function on arguments that have already been evaluated. This
+/// is how the shell evaluates a call such as `sin({1 + i})`. In ieee mode the
+/// elementary functions return infinities and NaNs rather than errors.
+pub fn call<T: Float>(name: &str, args: &[Complex<T>], mode: ArithmeticMode) -> Result<Value<Complex<T>>, ComplexError> {
+    if name == "roots" {
+        return match args {
+            [z, n] => Ok(Value::Vector(z.roots(positive_integer(name, n)?).collect())),
+            _ => Err(arity_error(name, 2, args)),
+        };
+    }
+
+    if matches!(name, "zr" | "zl" | "zc" | "ser" | "par" | "vdiv" | "power" | "pf" | "rms" | "peak") {
+        return ee::call(name, args);
+    }
+
+    let (strict, ieee): (Checked<T>, Unchecked<T>) = match name {
+        "conj" => (|z| Ok(z.conj()), Complex::conj),
+        "abs" => (|z| Ok(z.abs().into()), |z| z.abs().into()),
+        "arg" => (|z| Ok(z.arg().into()), |z| z.arg().into()),
+        "re" => (|z| Ok(z.re.into()), |z| z.re.into()),
+        "im" => (|z| Ok(z.im.into()), |z| z.im.into()),
+        "norm" => (|z| z.checked_norm().map(Complex::from), |z| (z.re * z.re + z.im * z.im).into()),
+        "sign" => (|z| Ok(z.sign()), Complex::sign),
+        "exp" => (Complex::exp, Complex::ieee_exp),
+        "ln" => (Complex::ln, Complex::ieee_ln),
+        "log10" => (Complex::log10, Complex::ieee_log10),
+        "sqrt" => (Complex::sqrt, Complex::ieee_sqrt),
+        "cbrt" => (Complex::cbrt, Complex::ieee_cbrt),
+        "sin" => (Complex::sin, Complex::ieee_sin),
+        "cos" => (Complex::cos, Complex::ieee_cos),
+        "tan" => (Complex::tan, Complex::ieee_tan),
+        "sec" => (Complex::sec, Complex::ieee_sec),
+        "csc" => (Complex::csc, Complex::ieee_csc),
+        "cot" => (Complex::cot, Complex::ieee_cot),
+        "sinh" => (Complex::sinh, Complex::ieee_sinh),
+        "cosh" => (Complex::cosh, Complex::ieee_cosh),
+        "tanh" => (Complex::tanh, Complex::ieee_tanh),
+        "asin" => (Complex::asin, Complex::ieee_asin),
+        "acos" => (Complex::acos, Complex::ieee_acos),
+        "atan" => (Complex::atan, Complex::ieee_atan),
+        "asinh" => (Complex::asinh, Complex::ieee_asinh),
+        "acosh" => (Complex::acosh, Complex::ieee_acosh),
+        "atanh" => (Complex::atanh, Complex::ieee_atanh),
+        _ => return Err(ComplexError::UnknownFunction(name.to_owned())),
+    };
+
+    match (args, mode) {
+        ([z], ArithmeticMode::Strict) => strict(z).map(Value::Number),
+        ([z], ArithmeticMode::Ieee) => Ok(Value::Number(ieee(z))),
+        _ => Err(arity_error(name, 1, args)),
+    }
+}
+
+type Checked<T> = fn(&Complex<T>) -> Result<Complex<T>, ComplexError>;
+type Unchecked<T> = fn(&Complex<T>) -> Complex<T>;
+
+pub(crate) fn arity_error<N>(name: &str, expected: usize, args: &[N]) -> ComplexError {
+    ComplexError::ArityMismatch { name: name.to_owned(), expected, found: args.len() }
+}
+
+// Interpret an argument as a count, which must be a real whole number of at
+// least one.
+fn positive_integer<T: Float>(name: &str, c: &Complex<T>) -> Result<u32, ComplexError> {
+    if c.im == T::ZERO && c.re.fract() == T::ZERO && c.re >= T::ONE && c.re <= T::from_f64(u32::MAX as f64) {
+        Ok(c.re.to_f64() as u32)
+    } else {
+        Err(ComplexError::InvalidArgument(format!("{name} needs a positive integer but was given {c}")))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    #[rstest(
+        name, args, expected,
+        case::sin("sin", vec![Complex::ZERO], "{0}"),
+        case::cos("cos", vec![Complex::ZERO], "{1}"),
+        case::sqrt("sqrt", vec![Complex::new(-4.0, 0.0)], "{2i}"),
+        case::exp("exp", vec![Complex::ZERO], "{1}"),
+        case::abs("abs", vec![Complex::new(-5.0, 12.0)], "{13}"),
+        case::sign("sign", vec![Complex::new(0.0, -2.0)], "{-i}"),
+        case::roots("roots", vec![Complex::new(4.0, 0.0), Complex::new(2.0, 0.0)], "[{2}, {-2}]"),
+        case::roots_single("roots", vec![Complex::new(4.0, 0.0), Complex::ONE], "[{4}]")
+    )]
+    fn call_works(name: &str, args: Vec<Complex>, expected: &str) {
+        let result = call(name, &args, ArithmeticMode::Strict).unwrap();
+        assert_eq!(expected, result.to_string());
+    }
+
+    #[rstest(
+        name, args, expected,
+        case::unknown("nope", vec![Complex::ZERO], ComplexError::UnknownFunction("nope".to_owned())),
+        case::no_args("sin", vec![], ComplexError::ArityMismatch { name: "sin".to_owned(), expected: 1, found: 0 }),
+        case::too_many_args("sin", vec![Complex::ZERO, Complex::ONE], ComplexError::ArityMismatch { name: "sin".to_owned(), expected: 1, found: 2 }),
+        case::not_finite("ln", vec![Complex::ZERO], ComplexError::DivisionByZero),
+        case::roots_one_arg("roots", vec![Complex::ONE], ComplexError::ArityMismatch { name: "roots".to_owned(), expected: 2, found: 1 })
+    )]
+    fn call_fails(name: &str, args: Vec<Complex>, expected: ComplexError) {
+        assert_eq!(Err(expected), call(name, &args, ArithmeticMode::Strict));
+    }
+
+    #[rstest(
+        n,
+        case::roots_zero(Complex::ZERO),
+        case::roots_fraction(Complex::new(1.5, 0.0)),
+        case::roots_complex(Complex::new(2.0, 1.0)),
+        case::roots_negative(Complex::new(-2.0, 0.0))
+    )]
+    fn roots_bad_count(n: Complex) {
+        let result = call("roots", &[Complex::ONE, n], ArithmeticMode::Strict);
+        assert!(matches!(result, Err(ComplexError::InvalidArgument(_))));
+    }
+}
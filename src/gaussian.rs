@@ -0,0 +1,413 @@
+use crate::complex::Complex;
+use crate::error::ComplexError;
+use crate::functions::arity_error;
+use crate::value::Value;
+
+/// A Gaussian integer, `a + bi` with whole number parts. `+`, `-` and `*`
+/// are exact (use the `checked_*` methods to catch overflow) and division is
+/// Euclidean, see `div_rem`.
+pub type GaussianInt = Complex<i64>;
+
+/// The factorisation of a Gaussian integer into a unit and Gaussian primes,
+/// as returned by `GaussianInt::factorise`. Each prime is normalised (see
+/// `GaussianInt::normalise`) and repeated as many times as it divides, and the
+/// primes are sorted by norm.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Factorisation {
+    pub unit: GaussianInt,
+    pub primes: Vec<GaussianInt>,
+}
+
+const UNITS: [GaussianInt; 4] = [GaussianInt::ONE, GaussianInt::I, Complex::new(-1, 0), GaussianInt::MINUS_I];
+
+impl Complex<i64> {
+    /// Euclidean division, giving `(q, r)` with `self = q * other + r` and
+    /// `norm(r) <= norm(other) / 2`. The quotient is `self / other` with each
+    /// part rounded to the nearest integer.
+    pub fn div_rem(&self, other: Self) -> Result<(Self, Self), ComplexError> {
+        let d = norm(&other);
+        if d == 0 {
+            return Err(ComplexError::DivisionByZero);
+        }
+
+        // self * conj(other); each product fits an i128 so only the sums
+        // need checking
+        let (a, b) = (self.re as i128, self.im as i128);
+        let (c, e) = (other.re as i128, other.im as i128);
+        let re = (a * c).checked_add(b * e).ok_or(ComplexError::Overflow)?;
+        let im = (b * c).checked_sub(a * e).ok_or(ComplexError::Overflow)?;
+
+        let d = i128::try_from(d).map_err(|_| ComplexError::Overflow)?;
+        let q = Complex::new(rounded_div(re, d)?, rounded_div(im, d)?);
+        let r = self.checked_sub(q.checked_mul(other)?)?;
+
+        Ok((q, r))
+    }
+
+    /// The associate of this number in the first quadrant, i.e. with
+    /// `re > 0` and `im >= 0`, along with the unit it was multiplied by to
+    /// get there. Zero is returned unchanged.
+    pub fn normalise(&self) -> Result<(Self, Self), ComplexError> {
+        if *self == Self::ZERO {
+            return Ok((Self::ZERO, Self::ONE));
+        }
+
+        for unit in UNITS {
+            let z = self.checked_mul(unit)?;
+            if z.re > 0 && z.im >= 0 {
+                return Ok((z, unit));
+            }
+        }
+
+        unreachable!("one associate of a non-zero number is in the first quadrant")
+    }
+
+    /// The greatest common divisor, normalised to the first quadrant. The gcd
+    /// of zero and zero is zero.
+    pub fn gcd(&self, other: Self) -> Result<Self, ComplexError> {
+        let (mut a, mut b) = (*self, other);
+
+        while b != Self::ZERO {
+            let (_, r) = a.div_rem(b)?;
+            a = b;
+            b = r;
+        }
+
+        Ok(a.normalise()?.0)
+    }
+
+    /// The extended gcd, giving `(g, x, y)` with `self * x + other * y = g`,
+    /// where `g` is the gcd as returned by `gcd`.
+    pub fn extended_gcd(&self, other: Self) -> Result<(Self, Self, Self), ComplexError> {
+        let (mut old_r, mut r) = (*self, other);
+        let (mut old_x, mut x) = (Self::ONE, Self::ZERO);
+        let (mut old_y, mut y) = (Self::ZERO, Self::ONE);
+
+        while r != Self::ZERO {
+            let (q, rem) = old_r.div_rem(r)?;
+            (old_r, r) = (r, rem);
+            (old_x, x) = (x, old_x.checked_sub(q.checked_mul(x)?)?);
+            (old_y, y) = (y, old_y.checked_sub(q.checked_mul(y)?)?);
+        }
+
+        let (g, unit) = old_r.normalise()?;
+
+        Ok((g, old_x.checked_mul(unit)?, old_y.checked_mul(unit)?))
+    }
+
+    /// Whether this is a Gaussian prime. A number on an axis is prime when
+    /// its magnitude is a prime of the form `4k + 3`, and any other number is
+    /// prime when its norm is prime, which must be below `2^63`.
+    pub fn is_prime(&self) -> Result<bool, ComplexError> {
+        if self.re == 0 || self.im == 0 {
+            let n = (self.re as i128 + self.im as i128).unsigned_abs();
+            Ok(n % 4 == 3 && is_prime(n))
+        } else {
+            Ok(is_prime(small_norm(self, "tested for primality")?))
+        }
+    }
+
+    /// Factorise into a unit and Gaussian primes, by way of the rational
+    /// primes dividing the norm, which must be below `2^63`.
+    pub fn factorise(&self) -> Result<Factorisation, ComplexError> {
+        if *self == Self::ZERO {
+            return Err(ComplexError::InvalidArgument("0 has no factorisation".to_owned()));
+        }
+
+        let mut factors = Vec::new();
+        prime_factors(small_norm(self, "factorised")?, &mut factors);
+        factors.sort_unstable();
+        factors.dedup();
+
+        let mut z = *self;
+        let mut primes = Vec::new();
+
+        for p in factors {
+            while norm(&z).is_multiple_of(p) {
+                let factor = gaussian_factor(p, &z)?;
+                z = z.div_rem(factor)?.0;
+                primes.push(factor);
+            }
+        }
+
+        primes.sort_by_key(|p| (norm(p), p.re, p.im));
+
+        // all that is left of z is a unit
+        Ok(Factorisation { unit: z, primes })
+    }
+}
+
+/// Call one of the functions available to Gaussian integers, e.g.
+/// `gcd({12 + 6i}, {6})`.
+pub fn call(name: &str, args: &[GaussianInt]) -> Result<Value<GaussianInt>, ComplexError> {
+    match (name, args) {
+        ("norm", [z]) => Ok(Value::Number(Complex::new(z.checked_norm()?, 0))),
+        ("conj", [z]) => Ok(Value::Number(Complex::new(z.re, z.im.checked_neg().ok_or(ComplexError::Overflow)?))),
+        ("isprime", [z]) => Ok(Value::Number(if z.is_prime()? { GaussianInt::ONE } else { GaussianInt::ZERO })),
+        ("factor", [z]) => {
+            let factorisation = z.factorise()?;
+            let mut v = Vec::new();
+            if factorisation.unit != GaussianInt::ONE {
+                v.push(factorisation.unit);
+            }
+            v.extend(factorisation.primes);
+            Ok(Value::Vector(v))
+        }
+        ("gcd", [a, b]) => Ok(Value::Number(a.gcd(*b)?)),
+        ("egcd", [a, b]) => {
+            let (g, x, y) = a.extended_gcd(*b)?;
+            Ok(Value::Vector(vec![g, x, y]))
+        }
+        ("divrem", [a, b]) => {
+            let (q, r) = a.div_rem(*b)?;
+            Ok(Value::Vector(vec![q, r]))
+        }
+        ("norm" | "conj" | "isprime" | "factor", _) => Err(arity_error(name, 1, args)),
+        ("gcd" | "egcd" | "divrem", _) => Err(arity_error(name, 2, args)),
+        _ => Err(ComplexError::UnknownFunction(name.to_owned())),
+    }
+}
+
+// The norm, which always fits a u128.
+fn norm(z: &GaussianInt) -> u128 {
+    let (re, im) = (z.re.unsigned_abs() as u128, z.im.unsigned_abs() as u128);
+    re * re + im * im
+}
+
+// n / d rounded to the nearest integer, with halves rounded up.
+fn rounded_div(n: i128, d: i128) -> Result<i64, ComplexError> {
+    let q = n.div_euclid(d);
+    let r = n.rem_euclid(d);
+    let q = if r >= d - r { q + 1 } else { q };
+
+    i64::try_from(q).map_err(|_| ComplexError::Overflow)
+}
+
+// The norm, if it is below 2^63, so that its prime factors fit an i64 and
+// products modulo it fit a u128.
+fn small_norm(z: &GaussianInt, action: &str) -> Result<u128, ComplexError> {
+    let n = norm(z);
+    if n > i64::MAX as u128 {
+        return Err(ComplexError::InvalidArgument(format!("{z} is too large to be {action}")));
+    }
+
+    Ok(n)
+}
+
+// Miller–Rabin, which with these bases is exact for n < 2^64 (Sorenson and
+// Webster, "Strong pseudoprimes to twelve prime bases", 2017).
+fn is_prime(n: u128) -> bool {
+    const BASES: [u128; 12] = [2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37];
+
+    if n < 2 {
+        return false;
+    }
+    if let Some(p) = BASES.iter().find(|p| n.is_multiple_of(**p)) {
+        return n == *p;
+    }
+
+    // n - 1 = d 2^s with d odd
+    let s = (n - 1).trailing_zeros();
+    let d = (n - 1) >> s;
+
+    BASES.iter().all(|a| {
+        let mut x = pow_mod(*a, d, n);
+        if x == 1 || x == n - 1 {
+            return true;
+        }
+        for _ in 1..s {
+            x = x * x % n;
+            if x == n - 1 {
+                return true;
+            }
+        }
+        false
+    })
+}
+
+// Push the prime factors of n < 2^64 onto the vector, with repeats, splitting
+// composites with Pollard's rho.
+fn prime_factors(n: u128, factors: &mut Vec<u128>) {
+    if n == 1 {
+        return;
+    } else if n.is_multiple_of(2) {
+        factors.push(2);
+        return prime_factors(n / 2, factors);
+    } else if is_prime(n) {
+        factors.push(n);
+        return;
+    }
+
+    let d = (1..).map(|c| rho(n, c)).find(|d| *d != n).expect("some c splits a composite");
+    prime_factors(d, factors);
+    prime_factors(n / d, factors);
+}
+
+// A divisor of the odd composite n that Floyd's cycle finding gives for the
+// sequence x² + c mod n, which is n itself if this c fails.
+fn rho(n: u128, c: u128) -> u128 {
+    let f = |x: u128| (x * x + c) % n;
+    let (mut x, mut y, mut d) = (2, 2, 1);
+
+    while d == 1 {
+        x = f(x);
+        y = f(f(y));
+        d = gcd(x.abs_diff(y), n);
+    }
+
+    d
+}
+
+fn gcd(a: u128, b: u128) -> u128 {
+    let (mut a, mut b) = (a, b);
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    a
+}
+
+// The Gaussian prime dividing z that lies over the rational prime p, which
+// divides the norm of z.
+fn gaussian_factor(p: u128, z: &GaussianInt) -> Result<GaussianInt, ComplexError> {
+    if p == 2 {
+        return Ok(Complex::new(1, 1));
+    }
+
+    let p_int = Complex::new(p as i64, 0);
+    if p % 4 == 3 {
+        return Ok(p_int);
+    }
+
+    // p = 1 mod 4 splits as π conj(π), where π = gcd(p, k + i) for k² = -1
+    // mod p, and it's whichever of the two divides z
+    let k = sqrt_minus_one(p);
+    let pi = p_int.gcd(Complex::new(k as i64, 1))?;
+
+    if z.div_rem(pi)?.1 == GaussianInt::ZERO {
+        Ok(pi)
+    } else {
+        Ok(Complex::new(pi.im, pi.re))
+    }
+}
+
+// A square root of -1 modulo a prime p = 1 mod 4. For a quadratic non-residue
+// a, a^((p - 1) / 4) is one.
+fn sqrt_minus_one(p: u128) -> u128 {
+    (2..p)
+        .find(|a| pow_mod(*a, (p - 1) / 2, p) == p - 1)
+        .map(|a| pow_mod(a, (p - 1) / 4, p))
+        .expect("a prime 1 mod 4 has a quadratic non-residue")
+}
+
+// b^e mod m, for m < 2^64 so that products fit a u128.
+fn pow_mod(b: u128, e: u128, m: u128) -> u128 {
+    let mut result = 1;
+    let mut b = b % m;
+    let mut e = e;
+
+    while e > 0 {
+        if e & 1 == 1 {
+            result = result * b % m;
+        }
+        b = b * b % m;
+        e >>= 1;
+    }
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    #[rstest(
+        a, b, q, r,
+        case::exact(Complex::new(4, 2), Complex::new(1, 1), Complex::new(3, -1), GaussianInt::ZERO),
+        case::remainder(Complex::new(7, 2), Complex::new(2, 1), Complex::new(3, -1), Complex::new(0, 1)),
+        case::real(Complex::new(7, 0), Complex::new(2, 0), Complex::new(4, 0), Complex::new(-1, 0))
+    )]
+    fn div_rem_works(a: GaussianInt, b: GaussianInt, q: GaussianInt, r: GaussianInt) {
+        assert_eq!(Ok((q, r)), a.div_rem(b));
+        assert_eq!(a, q * b + r);
+    }
+
+    #[test]
+    fn div_rem_by_zero() {
+        assert_eq!(Err(ComplexError::DivisionByZero), Complex::new(1, 1).div_rem(GaussianInt::ZERO));
+    }
+
+    #[rstest(
+        a, b, expected,
+        case::coprime(Complex::new(3, 0), Complex::new(2, 1), GaussianInt::ONE),
+        case::common(Complex::new(4, 2), Complex::new(6, 0), Complex::new(2, 0)),
+        case::with_zero(Complex::new(0, -5), GaussianInt::ZERO, Complex::new(5, 0)),
+        case::split(Complex::new(5, 0), Complex::new(3, 1), Complex::new(1, 2))
+    )]
+    fn gcd_works(a: GaussianInt, b: GaussianInt, expected: GaussianInt) {
+        assert_eq!(Ok(expected), a.gcd(b));
+    }
+
+    #[rstest(
+        a, b,
+        case::small(Complex::new(11, 3), Complex::new(1, 8)),
+        case::common(Complex::new(4, 2), Complex::new(6, 0)),
+        case::negative(Complex::new(-32, 9), Complex::new(4, -11))
+    )]
+    fn extended_gcd_works(a: GaussianInt, b: GaussianInt) {
+        let (g, x, y) = a.extended_gcd(b).unwrap();
+        assert_eq!(a.gcd(b), Ok(g));
+        assert_eq!(g, a * x + b * y);
+    }
+
+    #[rstest(
+        z, expected,
+        case::one_plus_i(Complex::new(1, 1), true),
+        case::three(Complex::new(3, 0), true),
+        case::minus_three_i(Complex::new(0, -3), true),
+        case::five(Complex::new(5, 0), false),
+        case::two(Complex::new(2, 0), false),
+        case::two_plus_i(Complex::new(2, 1), true),
+        case::unit(GaussianInt::I, false),
+        case::zero(GaussianInt::ZERO, false),
+        case::large_on_axis(Complex::new(0, 9223372036854775783), true),
+        case::large_norm(Complex::new(3747, 31400), true),
+        case::large_composite(Complex::new(-989888085, 141850658), false),
+        case::carmichael(Complex::new(561, 0), false)
+    )]
+    fn is_prime_works(z: GaussianInt, expected: bool) {
+        assert_eq!(Ok(expected), z.is_prime());
+    }
+
+    #[test]
+    fn too_large() {
+        let z = Complex::new(1 << 32, 1 << 32);
+        assert!(matches!(z.is_prime(), Err(ComplexError::InvalidArgument(_))));
+        assert!(matches!(z.factorise(), Err(ComplexError::InvalidArgument(_))));
+    }
+
+    #[rstest(
+        z, unit, primes,
+        case::two(Complex::new(2, 0), Complex::new(0, -1), vec![Complex::new(1, 1), Complex::new(1, 1)]),
+        case::five(Complex::new(5, 0), Complex::new(0, -1), vec![Complex::new(1, 2), Complex::new(2, 1)]),
+        case::prime(Complex::new(0, 3), GaussianInt::I, vec![Complex::new(3, 0)]),
+        case::unit(Complex::new(-1, 0), Complex::new(-1, 0), vec![]),
+        case::mixed(Complex::new(30, 0), Complex::new(-1, 0), vec![
+            Complex::new(1, 1), Complex::new(1, 1), Complex::new(1, 2), Complex::new(2, 1), Complex::new(3, 0)]),
+        case::large(Complex::new(-989888085, 141850658), GaussianInt::ONE, vec![
+            Complex::new(3747, 31400), Complex::new(745, 31614)]),
+        case::large_square(Complex::new(3037000427, 0), GaussianInt::ONE, vec![Complex::new(3037000427, 0)])
+    )]
+    fn factorise_works(z: GaussianInt, unit: GaussianInt, primes: Vec<GaussianInt>) {
+        let factorisation = z.factorise().unwrap();
+        assert_eq!(Factorisation { unit, primes: primes.clone() }, factorisation);
+        assert_eq!(z, primes.into_iter().fold(unit, |acc, p| acc * p));
+    }
+
+    #[test]
+    fn checked_pow_works() {
+        assert_eq!(Ok(Complex::new(-4, 0)), Complex::new(1, 1).checked_pow(4));
+        assert_eq!(Ok(GaussianInt::ONE), Complex::new(7, 3).checked_pow(0));
+        assert_eq!(Err(ComplexError::Overflow), Complex::new(1 << 20, 0).checked_pow(4));
+    }
+}
@@ -1,5 +1,20 @@
+pub mod error;
+pub mod num;
+pub mod angle;
 pub mod complex;
+pub mod trig;
+pub mod value;
+pub mod domain;
+pub mod gaussian;
+pub mod rational;
+pub mod bigfloat;
+pub mod functions;
+pub mod ee;
 pub mod parse_complex;
+pub mod span;
 pub mod tokenize;
+pub mod parser;
+pub mod environment;
+pub mod expr;
 pub mod process_tokens;
 pub mod format;
@@ -1,13 +1,40 @@
-use complex::process_tokens;
-use complex::tokenize::shunting_yard;
-use complex::tokenize::tokenize;
-use process_tokens::process;
+use complex::angle::{self, AngleUnit};
+use complex::bigfloat::{self, BigFloat};
+use complex::domain::Domain;
+use complex::gaussian::GaussianInt;
+use complex::rational::RationalComplex;
+use complex::environment::Environment;
+use complex::error::ComplexError;
+use complex::expr::Statement;
+use complex::span::Span;
+use complex::complex::{ArithmeticMode, Complex};
 use std::io::{self, Write};
 
+// The number system that input is evaluated in, chosen with `:domain`.
+#[derive(Clone, Copy)]
+enum Mode {
+    Float,
+    Exact,
+    Rational,
+    BigFloat,
+}
+
+// The variables of each domain, which are kept apart as their numbers differ.
+#[derive(Default)]
+struct Session {
+    float: Environment<Complex>,
+    exact: Environment<GaussianInt>,
+    rational: Environment<RationalComplex>,
+    bigfloat: Environment<Complex<BigFloat>>,
+}
+
 fn main() {
     println!("Complex shell!");
 
     let mut running = true;
+    let mut mode = Mode::Float;
+    let mut arithmetic = ArithmeticMode::Strict;
+    let mut session = Session::default();
 
     while running {
         print!("c$ ");
@@ -15,31 +42,115 @@ fn main() {
 
         let mut input = String::new();
 
-        io::stdin()
+        let read = io::stdin()
             .read_line(&mut input)
             .expect("Failed to read input");
 
-        println!("Input is {}", input);
+        // stdin has closed, as at the end of a piped script
+        if read == 0 {
+            println!();
+            break;
+        }
+
+        let command = input.trim().to_lowercase();
 
-        if input.trim().to_lowercase() == "quit" {
+        if command.is_empty() {
+            continue;
+        } else if command == "quit" {
             println!("Bye...");
             running = false;
+        } else if let Some(domain) = command.strip_prefix(":domain") {
+            match domain.trim() {
+                "float" => mode = Mode::Float,
+                "exact" => mode = Mode::Exact,
+                "rational" => mode = Mode::Rational,
+                // at whatever precision was last set with `:precision`
+                "bigfloat" => mode = Mode::BigFloat,
+                _ => println!("ERROR: :domain must be float, exact, rational or bigfloat"),
+            }
+        } else if let Some(arithmetic_mode) = command.strip_prefix(":arithmetic") {
+            match arithmetic_mode.trim() {
+                "strict" => arithmetic = ArithmeticMode::Strict,
+                "ieee" => arithmetic = ArithmeticMode::Ieee,
+                _ => println!("ERROR: :arithmetic must be strict or ieee"),
+            }
+        } else if let Some(unit) = command.strip_prefix(":angle") {
+            match unit.trim().parse::<AngleUnit>() {
+                Ok(unit) => angle::set_angle_unit(unit),
+                Err(_) => println!("ERROR: :angle must be deg, rad or grad"),
+            }
+        } else if let Some(bits) = command.strip_prefix(":precision") {
+            match bits.trim().parse().map_err(|_| "precision must be a number of bits".to_owned())
+                .and_then(|bits| bigfloat::set_precision(bits).map_err(|e| e.to_string())) {
+                Ok(()) => mode = Mode::BigFloat,
+                Err(e) => println!("ERROR: {}", e),
+            }
+        } else if command == ":vars" {
+            match mode {
+                Mode::Float => show_variables(&session.float, 6),
+                Mode::Exact => show_variables(&session.exact, 6),
+                Mode::Rational => show_variables(&session.rational, 6),
+                Mode::BigFloat => show_variables(&session.bigfloat, bigfloat_places()),
+            }
+        } else if command == ":clear" {
+            session = Session::default();
         } else {
-            let tokenized = tokenize(&input);
-            println!("Tokenized = {:?}", tokenized);
-
-            let mut shunted = shunting_yard(tokenized.unwrap());
-            println!("Shunted = {:?}", shunted);
-
-            let r = process(&mut shunted);
-            match r {
-                Ok(c) => {
-                    println!("Result = {}", c);
-                },
-                Err(e) => {
-                    println!("ERROR: {}", e);
-                }
+            let line = input.trim_end();
+            match mode {
+                Mode::Float => run(line, &mut session.float, 6, arithmetic),
+                Mode::Exact => run(line, &mut session.exact, 6, arithmetic),
+                Mode::Rational => run(line, &mut session.rational, 6, arithmetic),
+                Mode::BigFloat => run(line, &mut session.bigfloat, bigfloat_places(), arithmetic),
             }
         }
     }
 }
+
+// Show all the decimal places that the precision is good for.
+fn bigfloat_places() -> usize {
+    (bigfloat::precision() as f64 * 2f64.log10()) as usize - 1
+}
+
+fn show_variables<N: Domain>(env: &Environment<N>, places: usize) {
+    for (name, value) in env.variables() {
+        println!("{} = {:.2$}", name, value, places);
+    }
+
+    for (name, function) in env.functions() {
+        println!("{}({}) = {:.3$}", name, function.params.join(", "), function.body, places);
+    }
+}
+
+fn run<N: Domain>(line: &str, env: &mut Environment<N>, places: usize, arithmetic: ArithmeticMode) {
+    let statement = match Statement::<N>::parse(line) {
+        Ok(statement) => statement,
+        Err(e) => {
+            report(line, &e.error, Some(e.span));
+            return;
+        }
+    };
+
+    let r = statement.execute(env, arithmetic);
+    match r {
+        Ok(Some(c)) => {
+            println!("Result = {:.1$}", c, places);
+        },
+        Ok(None) => {
+            println!("Defined {}", statement);
+        },
+        Err(e) => {
+            report(line, &e, None);
+        }
+    }
+}
+
+// Print an error, beneath the input line with the part it is about underlined
+// when that is known.
+fn report(line: &str, e: &ComplexError, span: Option<Span>) {
+    if let Some(span) = span {
+        println!("{}", line);
+        println!("{}", span.underline(line));
+    }
+
+    println!("ERROR: {}", e);
+}
@@ -0,0 +1,290 @@
+use std::{fmt, ops::{Add, Div, Mul, Neg, Sub}};
+
+use crate::error::ComplexError;
+use crate::format::{format_float, format_round_trip};
+
+/// The numbers that can make up the real and imaginary parts of a `Complex`.
+/// Anything that can be added, subtracted and multiplied will do, so this
+/// includes the signed integers as well as the floats.
+pub trait Num:
+    Copy
+    + PartialEq
+    + PartialOrd
+    + fmt::Debug
+    + Add<Output = Self>
+    + Sub<Output = Self>
+    + Mul<Output = Self>
+    + Neg<Output = Self>
+{
+    const ZERO: Self;
+    const ONE: Self;
+    const MINUS_ONE: Self;
+
+    /// Add, returning an error if the result can't be represented.
+    fn checked_add(self, other: Self) -> Result<Self, ComplexError>;
+
+    /// Subtract, returning an error if the result can't be represented.
+    fn checked_sub(self, other: Self) -> Result<Self, ComplexError>;
+
+    /// Multiply, returning an error if the result can't be represented.
+    fn checked_mul(self, other: Self) -> Result<Self, ComplexError>;
+
+    /// Parse an unsigned or signed decimal literal, e.g. `12`, `-0.5` or
+    /// `1.2e-7`, or `None` if it isn't a number of this type.
+    fn from_decimal_str(s: &str) -> Option<Self>;
+
+    /// Format for display, using at most `precision` decimal places and
+    /// switching to exponent form beyond `magnitude` digits where that makes
+    /// sense for the type. See `format::format_f64`.
+    fn to_std_string(&self, magnitude: usize, precision: usize) -> String;
+
+    /// Format as the shortest decimal that parses back to exactly this value.
+    fn to_literal_string(&self) -> String;
+}
+
+/// Floating point numbers, which complex division and the transcendental
+/// functions need. The methods mirror those of `f64`.
+pub trait Float: Num + Div<Output = Self> + fmt::Display + fmt::LowerExp {
+    fn from_f64(f: f64) -> Self;
+    fn to_f64(self) -> f64;
+
+    /// π
+    fn pi() -> Self;
+    /// ln(10)
+    fn ln_10() -> Self;
+    /// The largest finite value.
+    fn max_value() -> Self;
+    /// The smallest positive normal value.
+    fn min_positive() -> Self;
+    /// The difference between 1 and the next value up.
+    fn epsilon() -> Self;
+    /// Positive infinity.
+    fn infinity() -> Self;
+
+    /// Divide, returning an error if the result isn't finite.
+    fn checked_div(self, other: Self) -> Result<Self, ComplexError>;
+
+    fn is_finite(self) -> bool;
+    fn is_nan(self) -> bool;
+    fn is_infinite(self) -> bool;
+
+    fn abs(self) -> Self;
+    fn max(self, other: Self) -> Self;
+    fn floor(self) -> Self;
+    fn fract(self) -> Self;
+    fn rem_euclid(self, other: Self) -> Self;
+    fn copysign(self, sign: Self) -> Self;
+    fn hypot(self, other: Self) -> Self;
+    fn sqrt(self) -> Self;
+    fn cbrt(self) -> Self;
+    fn powf(self, n: Self) -> Self;
+    fn exp(self) -> Self;
+    fn ln(self) -> Self;
+    fn log10(self) -> Self;
+    fn sin(self) -> Self;
+    fn cos(self) -> Self;
+    fn sin_cos(self) -> (Self, Self);
+    fn tan(self) -> Self;
+    fn atan2(self, other: Self) -> Self;
+    fn sinh(self) -> Self;
+    fn cosh(self) -> Self;
+    fn asinh(self) -> Self;
+}
+
+// A float result, or the reason it isn't finite.
+pub(crate) fn finite_or_err<T: Float>(f: T) -> Result<T, ComplexError> {
+    if f.is_nan() {
+        Err(ComplexError::NaNProduced)
+    } else if f.is_infinite() {
+        Err(ComplexError::Overflow)
+    } else {
+        Ok(f)
+    }
+}
+
+macro_rules! impl_float {
+    ($t:ident) => {
+        impl Num for $t {
+            const ZERO: Self = 0.0;
+            const ONE: Self = 1.0;
+            const MINUS_ONE: Self = -1.0;
+
+            fn checked_add(self, other: Self) -> Result<Self, ComplexError> {
+                finite_or_err(self + other)
+            }
+
+            fn checked_sub(self, other: Self) -> Result<Self, ComplexError> {
+                finite_or_err(self - other)
+            }
+
+            fn checked_mul(self, other: Self) -> Result<Self, ComplexError> {
+                finite_or_err(self * other)
+            }
+
+            fn from_decimal_str(s: &str) -> Option<Self> {
+                s.parse().ok()
+            }
+
+            fn to_std_string(&self, magnitude: usize, precision: usize) -> String {
+                format_float(*self, magnitude, precision)
+            }
+
+            fn to_literal_string(&self) -> String {
+                format_round_trip(*self)
+            }
+        }
+
+        impl Float for $t {
+            fn from_f64(f: f64) -> Self {
+                f as $t
+            }
+
+            fn to_f64(self) -> f64 {
+                self as f64
+            }
+
+            fn pi() -> Self {
+                std::$t::consts::PI
+            }
+
+            fn ln_10() -> Self {
+                std::$t::consts::LN_10
+            }
+
+            fn max_value() -> Self {
+                $t::MAX
+            }
+
+            fn min_positive() -> Self {
+                $t::MIN_POSITIVE
+            }
+
+            fn epsilon() -> Self {
+                $t::EPSILON
+            }
+
+            fn infinity() -> Self {
+                $t::INFINITY
+            }
+
+            fn checked_div(self, other: Self) -> Result<Self, ComplexError> {
+                finite_or_err(self / other)
+            }
+
+            fn is_finite(self) -> bool { $t::is_finite(self) }
+            fn is_nan(self) -> bool { $t::is_nan(self) }
+            fn is_infinite(self) -> bool { $t::is_infinite(self) }
+
+            fn abs(self) -> Self { $t::abs(self) }
+            fn max(self, other: Self) -> Self { $t::max(self, other) }
+            fn floor(self) -> Self { $t::floor(self) }
+            fn fract(self) -> Self { $t::fract(self) }
+            fn rem_euclid(self, other: Self) -> Self { $t::rem_euclid(self, other) }
+            fn copysign(self, sign: Self) -> Self { $t::copysign(self, sign) }
+            fn hypot(self, other: Self) -> Self { $t::hypot(self, other) }
+            fn sqrt(self) -> Self { $t::sqrt(self) }
+            fn cbrt(self) -> Self { $t::cbrt(self) }
+            fn powf(self, n: Self) -> Self { $t::powf(self, n) }
+            fn exp(self) -> Self { $t::exp(self) }
+            fn ln(self) -> Self { $t::ln(self) }
+            fn log10(self) -> Self { $t::log10(self) }
+            fn sin(self) -> Self { $t::sin(self) }
+            fn cos(self) -> Self { $t::cos(self) }
+            fn sin_cos(self) -> (Self, Self) { $t::sin_cos(self) }
+            fn tan(self) -> Self { $t::tan(self) }
+            fn atan2(self, other: Self) -> Self { $t::atan2(self, other) }
+            fn sinh(self) -> Self { $t::sinh(self) }
+            fn cosh(self) -> Self { $t::cosh(self) }
+            fn asinh(self) -> Self { $t::asinh(self) }
+        }
+    };
+}
+
+impl_float!(f32);
+impl_float!(f64);
+
+macro_rules! impl_int {
+    ($t:ident) => {
+        impl Num for $t {
+            const ZERO: Self = 0;
+            const ONE: Self = 1;
+            const MINUS_ONE: Self = -1;
+
+            fn checked_add(self, other: Self) -> Result<Self, ComplexError> {
+                $t::checked_add(self, other).ok_or(ComplexError::Overflow)
+            }
+
+            fn checked_sub(self, other: Self) -> Result<Self, ComplexError> {
+                $t::checked_sub(self, other).ok_or(ComplexError::Overflow)
+            }
+
+            fn checked_mul(self, other: Self) -> Result<Self, ComplexError> {
+                $t::checked_mul(self, other).ok_or(ComplexError::Overflow)
+            }
+
+            fn from_decimal_str(s: &str) -> Option<Self> {
+                s.parse().ok()
+            }
+
+            fn to_std_string(&self, _magnitude: usize, _precision: usize) -> String {
+                self.to_string()
+            }
+
+            fn to_literal_string(&self) -> String {
+                self.to_string()
+            }
+        }
+    };
+}
+
+impl_int!(i8);
+impl_int!(i16);
+impl_int!(i32);
+impl_int!(i64);
+impl_int!(i128);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    #[rstest(
+        a, b, expected,
+        case::ok(1.0, 2.0, Ok(3.0)),
+        case::overflow(f64::MAX, f64::MAX, Err(ComplexError::Overflow)),
+        case::nan(f64::INFINITY, f64::NEG_INFINITY, Err(ComplexError::NaNProduced))
+    )]
+    fn float_checked_add(a: f64, b: f64, expected: Result<f64, ComplexError>) {
+        assert_eq!(expected, Num::checked_add(a, b));
+    }
+
+    #[rstest(
+        a, b, expected,
+        case::ok(3, -4, Ok(-12)),
+        case::overflow(i64::MAX, 2, Err(ComplexError::Overflow))
+    )]
+    fn int_checked_mul(a: i64, b: i64, expected: Result<i64, ComplexError>) {
+        assert_eq!(expected, Num::checked_mul(a, b));
+    }
+
+    #[rstest(
+        s, expected,
+        case::int("12", Some(12)),
+        case::negative("-7", Some(-7)),
+        case::decimal("1.5", None),
+        case::exponent("1e3", None)
+    )]
+    fn int_from_decimal_str(s: &str, expected: Option<i32>) {
+        assert_eq!(expected, i32::from_decimal_str(s));
+    }
+
+    #[rstest(
+        f, expected,
+        case::f32_tenth(0.1_f32, "0.1"),
+        case::f32_big(123456789.0_f32, "1.234568e8"),
+        case::f32_tiny(0.000001_f32, "1e-6")
+    )]
+    fn f32_string(f: f32, expected: &str) {
+        assert_eq!(expected, f.to_std_string(6, 6));
+    }
+}
@@ -1,128 +1,420 @@
-use crate::complex::Complex;
+use std::{fmt, str::FromStr};
 
-pub fn from_str(s: &str) -> Result<Complex, String> {
-    let result = from_bracket_form(s)
-        .or_else(|| from_standard_form(s))
-        .or_else(|| from_standard_form_with_brackets(s))
-        .or_else(|| from_polar_form(s));
+use crate::angle::{angle_unit, AngleUnit};
+use crate::complex::{Complex, PolarComplex};
+use crate::error::ComplexError;
+use crate::num::{Float, Num};
 
-    result.ok_or(format!("Cannot parse '{s}' to a complex number"))
+/// The textual forms a complex number can be written in.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum LiteralFormat {
+    /// `{a, b}`
+    Bracket,
+    /// `a + bi`, or `a + bj`
+    Standard,
+    /// `{a + bi}`
+    BracketedStandard,
+    /// `@{r, theta}`
+    Polar,
+    /// `r∠theta`, as phasors are written, optionally in brackets
+    Phasor,
 }
 
-/// Parse a complex number from the bracketed form `{a, b}`.
-fn from_bracket_form(s: &str) -> Option<Complex> {
-    let parts: Vec<&str> = s
-        .trim()
-        .strip_prefix("{")?
-        .strip_suffix("}")?
-        .split(',')
-        .map(|s| s.trim())
-        .collect();
+impl fmt::Display for LiteralFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LiteralFormat::Bracket => write!(f, "bracket form {{a, b}}"),
+            LiteralFormat::Standard => write!(f, "standard form a + bi"),
+            LiteralFormat::BracketedStandard => write!(f, "bracketed standard form {{a + bi}}"),
+            LiteralFormat::Polar => write!(f, "polar form @{{r, theta}}"),
+            LiteralFormat::Phasor => write!(f, "phasor form r∠theta"),
+        }
+    }
+}
+
+/// Parse a complex number written in any of the forms of `LiteralFormat`. The
+/// form is chosen from how the text starts, so an error reports the form that
+/// was attempted and the byte offset of the first character that doesn't fit
+/// it.
+pub fn from_str(s: &str) -> Result<Complex, ComplexError> {
+    parse(s)
+}
 
-    if parts.len() != 2 {
-        return None;
+/// `from_str` for any width of float.
+pub fn parse<T: Float>(s: &str) -> Result<Complex<T>, ComplexError> {
+    match literal_format(s) {
+        LiteralFormat::Polar => from_polar_form(s).map(|p| p.to_complex()),
+        LiteralFormat::Phasor => from_phasor_form(s).map(|p| p.to_complex()),
+        _ => from_rectangular_str(s),
     }
+}
+
+/// Parse a complex number written in any form other than polar, which is all
+/// that makes sense for parts that aren't floats.
+pub fn from_rectangular_str<T: Num>(s: &str) -> Result<Complex<T>, ComplexError> {
+    match literal_format(s) {
+        LiteralFormat::Bracket => from_bracket_form(s),
+        LiteralFormat::Standard => from_standard_form(s),
+        LiteralFormat::BracketedStandard => from_standard_form_with_brackets(s),
+        format @ (LiteralFormat::Polar | LiteralFormat::Phasor) => Err(ComplexError::ParseError {
+            input: s.to_owned(),
+            format,
+            position: s.len() - s.trim_start().len(),
+        }),
+    }
+}
 
-    let re = parts[0].parse::<f64>().ok()?;
-    let im = parts[1].parse::<f64>().ok()?;
+impl<T: Float> FromStr for Complex<T> {
+    type Err = ComplexError;
 
-    Some(Complex::new(re, im))
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        parse(s)
+    }
 }
 
-/// Parse a complex number from the standard form 'a + bi'. This works
-/// where numbers can be negative or are exponential, e.g. `-1.2e-7 - 3.0e-10`
-/// will work just fine.
-fn from_standard_form(s: &str) -> Option<Complex> {
-    // Strip out all whitespace, but ensure that single spaces only
-    // exist before number signs. Make the other possible instance of
-    // number signs, in exponents, safe from this though.
-    let cleanish: String = s
-        .to_lowercase()
-        .replace("e-", "eM")
-        .replace("e+", "eP")
-        .chars()
-        .filter(|c| !c.is_whitespace())
-        .flat_map(|c| {
-            if c == '+' || c == '-' {
-                vec![' ', c]
-            } else {
-                vec![c]
+impl<T: Float> TryFrom<&str> for Complex<T> {
+    type Error = ComplexError;
+
+    fn try_from(s: &str) -> Result<Self, Self::Error> {
+        parse(s)
+    }
+}
+
+macro_rules! int_from_str {
+    ($t:ident) => {
+        impl FromStr for Complex<$t> {
+            type Err = ComplexError;
+
+            fn from_str(s: &str) -> Result<Self, Self::Err> {
+                from_rectangular_str(s)
             }
-        })
-        .collect();
+        }
+
+        impl TryFrom<&str> for Complex<$t> {
+            type Error = ComplexError;
+
+            fn try_from(s: &str) -> Result<Self, Self::Error> {
+                from_rectangular_str(s)
+            }
+        }
+    };
+}
 
-    // an edge case with redundant `+` as in `a + -bi`.
-    let cleaned = cleanish.replace("+ -", "-");
+int_from_str!(i8);
+int_from_str!(i16);
+int_from_str!(i32);
+int_from_str!(i64);
+int_from_str!(i128);
 
-    // split on the valid spaces to get only 1 or 2 parts
-    let parts: Vec<&str> = cleaned.split(' ').filter(|p| !p.is_empty()).collect();
+/// Polar numbers are parsed as written, without going through rectangular form,
+/// so the modulus and angle are kept exactly. Any other form is converted.
+impl<T: Float> FromStr for PolarComplex<T> {
+    type Err = ComplexError;
 
-    // one part -> number is real only or imaginary only
-    // two parts -> number has real part and imaginary part
-    if parts.len() == 1 {
-        if parts[0].contains('i') {
-            let im = imaginary_value(parts[0]).ok()?;
-            return Some(Complex::new(0.0, im));
-        } else {
-            let re = real_value(parts[0]).ok()?;
-            return Some(Complex::new(re, 0.0));
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match literal_format(s) {
+            LiteralFormat::Polar => from_polar_form(s),
+            LiteralFormat::Phasor => from_phasor_form(s),
+            _ => parse(s).map(|c| c.to_polar()),
         }
-    } else if parts.len() == 2 {
-        let re = real_value(parts[0]).ok()?;
-        let im = imaginary_value(parts[1]).ok()?;
-        return Some(Complex::new(re, im));
     }
+}
+
+impl<T: Float> TryFrom<&str> for PolarComplex<T> {
+    type Error = ComplexError;
 
-    None
+    fn try_from(s: &str) -> Result<Self, Self::Error> {
+        s.parse()
+    }
 }
 
-fn from_standard_form_with_brackets(s: &str) -> Option<Complex> {
-    let removed_brackets = s.trim().strip_prefix("{")?.strip_suffix("}")?;
+fn literal_format(s: &str) -> LiteralFormat {
+    let trimmed = s.trim_start();
 
-    from_standard_form(removed_brackets)
+    if trimmed.starts_with('@') {
+        LiteralFormat::Polar
+    } else if trimmed.contains('∠') {
+        LiteralFormat::Phasor
+    } else if trimmed.starts_with('{') && trimmed.contains(',') {
+        LiteralFormat::Bracket
+    } else if trimmed.starts_with('{') {
+        LiteralFormat::BracketedStandard
+    } else {
+        LiteralFormat::Standard
+    }
 }
 
-fn from_polar_form(s: &str) -> Option<Complex> {
-    let cleaned: String = s.chars().filter(|c| !c.is_whitespace()).collect();
+/// Parse a complex number from the bracketed form `{a, b}`.
+fn from_bracket_form<T: Num>(s: &str) -> Result<Complex<T>, ComplexError> {
+    let mut p = Parser::new(s, LiteralFormat::Bracket);
 
-    let parts: Vec<&str> = cleaned
-        .strip_prefix("@{")?
-        .strip_suffix("}")?
-        .split(',')
-        .collect();
+    p.expect('{')?;
+    let re = p.number(true)?;
+    p.expect(',')?;
+    let im = p.number(true)?;
+    p.expect('}')?;
+    p.end()?;
 
-    if parts.len() != 2 {
-        return None;
-    }
+    Ok(Complex::new(re, im))
+}
 
-    let r = parts[0].parse::<f64>().ok()?;
-    let theta = parts[1].parse::<f64>().ok()?;
+/// Parse a complex number from the standard form 'a + bi'. This works
+/// where numbers can be negative or are exponential, e.g. `-1.2e-7 - 3.0e-10i`
+/// will work just fine.
+fn from_standard_form<T: Num>(s: &str) -> Result<Complex<T>, ComplexError> {
+    let mut p = Parser::new(s, LiteralFormat::Standard);
 
-    Some(Complex::new(r * theta.cos(), r * theta.sin()))
+    let c = p.standard(None)?;
+    p.end()?;
+
+    Ok(c)
 }
 
-/// Parse to number after removing the i suffix, but also deal with special cases of
-/// `i`. `+i` and `-i`. Put any exponents back to their correct form.
-fn imaginary_value(s: &str) -> Result<f64, std::num::ParseFloatError> {
-    match s {
-        "i" => Ok(1.0),
-        "+i" => Ok(1.0),
-        "-i" => Ok(-1.0),
-        _ => s[..s.len() - 1]
-            .replace("eM", "e-")
-            .replace("eP", "e+")
-            .parse::<f64>(),
+/// Parse a complex number from the standard form in brackets, `{a + bi}`.
+fn from_standard_form_with_brackets<T: Num>(s: &str) -> Result<Complex<T>, ComplexError> {
+    let mut p = Parser::new(s, LiteralFormat::BracketedStandard);
+
+    p.expect('{')?;
+    let c = p.standard(Some('}'))?;
+    p.expect('}')?;
+    p.end()?;
+
+    Ok(c)
+}
+
+/// Parse a number in polar form, `@{r, theta}`. Theta may have a unit suffix,
+/// as in `@{10, 45deg}`, and is otherwise in the unit of `angle::angle_unit`.
+fn from_polar_form<T: Float>(s: &str) -> Result<PolarComplex<T>, ComplexError> {
+    let mut p = Parser::new(s, LiteralFormat::Polar);
+
+    p.expect('@')?;
+    p.expect('{')?;
+    let r = p.number(true)?;
+    p.expect(',')?;
+    let theta = p.angle()?;
+    p.expect('}')?;
+    p.end()?;
+
+    Ok(PolarComplex::new(r, theta))
+}
+
+/// Parse a phasor, `r∠theta` or `{r∠theta}`, with the angle as for
+/// `from_polar_form`, e.g. `10∠45°`.
+fn from_phasor_form<T: Float>(s: &str) -> Result<PolarComplex<T>, ComplexError> {
+    let mut p = Parser::new(s, LiteralFormat::Phasor);
+
+    let bracketed = p.eat('{');
+    let r = p.number(true)?;
+    p.expect('∠')?;
+    let theta = p.angle()?;
+    if bracketed {
+        p.expect('}')?;
     }
+    p.end()?;
+
+    Ok(PolarComplex::new(r, theta))
 }
 
-// Parse to number but fix any exponents back to their correct form first.
-fn real_value(s: &str) -> Result<f64, std::num::ParseFloatError> {
-    s.replace("eM", "e-").replace("eP", "e+").parse::<f64>()
+// Steps through a literal a character at a time, keeping track of the byte
+// offset so that errors can point at the offending character. Whitespace is
+// allowed between the parts of a number, but not inside the numbers themselves.
+struct Parser<'a> {
+    input: &'a str,
+    pos: usize,
+    format: LiteralFormat,
+}
+
+impl<'a> Parser<'a> {
+    fn new(input: &'a str, format: LiteralFormat) -> Self {
+        Parser { input, pos: 0, format }
+    }
+
+    fn error(&self) -> ComplexError {
+        ComplexError::ParseError {
+            input: self.input.to_owned(),
+            format: self.format,
+            position: self.pos,
+        }
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.input[self.pos..].chars().next()
+    }
+
+    fn bump(&mut self) {
+        if let Some(c) = self.peek() {
+            self.pos += c.len_utf8();
+        }
+    }
+
+    fn skip_whitespace(&mut self) {
+        while self.peek().is_some_and(char::is_whitespace) {
+            self.bump();
+        }
+    }
+
+    fn eat(&mut self, c: char) -> bool {
+        self.skip_whitespace();
+        if self.peek() == Some(c) {
+            self.bump();
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect(&mut self, c: char) -> Result<(), ComplexError> {
+        if self.eat(c) { Ok(()) } else { Err(self.error()) }
+    }
+
+    fn end(&mut self) -> Result<(), ComplexError> {
+        self.skip_whitespace();
+        if self.peek().is_none() { Ok(()) } else { Err(self.error()) }
+    }
+
+    fn digits(&mut self) -> bool {
+        let start = self.pos;
+        while self.peek().is_some_and(|c| c.is_ascii_digit()) {
+            self.bump();
+        }
+        self.pos > start
+    }
+
+    // Any number of `+` and `-` signs, so that the redundant `a + -bi` works.
+    // Gives the overall sign, or `None` if there are no signs at all.
+    fn signs<T: Num>(&mut self) -> Option<T> {
+        let mut sign = None;
+        loop {
+            if self.eat('+') {
+                sign = Some(sign.unwrap_or(T::ONE));
+            } else if self.eat('-') {
+                sign = Some(-sign.unwrap_or(T::ONE));
+            } else {
+                return sign;
+            }
+        }
+    }
+
+    // A decimal number, e.g. `12`, `-0.5` or `1.2E-7`, or a fraction `1/3`.
+    fn number<T: Num>(&mut self, signed: bool) -> Result<T, ComplexError> {
+        self.skip_whitespace();
+        let start = self.pos;
+
+        if signed && matches!(self.peek(), Some('+' | '-')) {
+            self.bump();
+        }
+
+        let whole = self.digits();
+        let fraction = self.peek() == Some('.') && {
+            self.bump();
+            self.digits()
+        };
+        if !whole && !fraction {
+            return Err(self.error());
+        }
+
+        if matches!(self.peek(), Some('e' | 'E')) {
+            self.bump();
+            if matches!(self.peek(), Some('+' | '-')) {
+                self.bump();
+            }
+            if !self.digits() {
+                return Err(self.error());
+            }
+        }
+
+        // a fraction, for the types that can represent one exactly
+        if self.peek() == Some('/') {
+            self.bump();
+            if !self.digits() {
+                return Err(self.error());
+            }
+        }
+
+        T::from_decimal_str(&self.input[start..self.pos]).ok_or_else(|| {
+            // the number is well formed, just not of this type
+            self.pos = start;
+            self.error()
+        })
+    }
+
+    // An angle in radians, read in the unit of its suffix if it has one and
+    // the current angle unit if not.
+    fn angle<T: Float>(&mut self) -> Result<T, ComplexError> {
+        let theta = self.number(true)?;
+
+        // grad before rad, which it ends with
+        let unit = ["°", "deg", "grad", "rad"]
+            .into_iter()
+            .find(|suffix| self.eat_str(suffix))
+            .map_or(Ok(angle_unit()), str::parse::<AngleUnit>)?;
+
+        Ok(unit.to_radians(theta))
+    }
+
+    fn eat_str(&mut self, s: &str) -> bool {
+        self.skip_whitespace();
+        if self.input[self.pos..].starts_with(s) {
+            self.pos += s.len();
+            true
+        } else {
+            false
+        }
+    }
+
+    // A real or imaginary term without a sign, i.e. `a`, `bi` or `i`, giving
+    // its value and whether it was imaginary. Engineers' `j` will do for `i`.
+    fn term<T: Num>(&mut self) -> Result<(T, bool), ComplexError> {
+        self.skip_whitespace();
+        if matches!(self.peek(), Some('i' | 'I' | 'j' | 'J')) {
+            self.bump();
+            return Ok((T::ONE, true));
+        }
+
+        let value = self.number(false)?;
+        if matches!(self.peek(), Some('i' | 'I' | 'j' | 'J')) {
+            self.bump();
+            Ok((value, true))
+        } else {
+            Ok((value, false))
+        }
+    }
+
+    // `a`, `bi` or `a + bi` with optional signs, ending at the end of the input
+    // or just before the closing character.
+    fn standard<T: Num>(&mut self, close: Option<char>) -> Result<Complex<T>, ComplexError> {
+        let sign = self.signs().unwrap_or(T::ONE);
+        let (first, first_imaginary) = self.term()?;
+
+        self.skip_whitespace();
+        if self.peek() == close {
+            return Ok(if first_imaginary {
+                Complex::new(T::ZERO, sign * first)
+            } else {
+                Complex::new(sign * first, T::ZERO)
+            });
+        }
+
+        // only an imaginary part can follow a real part
+        if first_imaginary {
+            return Err(self.error());
+        }
+
+        let op: T = self.signs().ok_or_else(|| self.error())?;
+        let (second, second_imaginary) = self.term()?;
+        if !second_imaginary {
+            self.skip_whitespace();
+            return Err(self.error());
+        }
+
+        Ok(Complex::new(sign * first, op * second))
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::angle::set_angle_unit;
     use rstest::rstest;
 
     // Assert that two complex numbers are close to each other, with
@@ -159,6 +451,9 @@ mod tests {
         case::plain_imaginary_only_negative("-13i", Complex::new(0.0, -13.0)),
         case::plain_imaginary_only_exponent("1.2e-7i", Complex::new(0.0, 1.2e-7)),
         case::plain_imaginary_i_only("i", Complex::new(0.0, 1.0)),
+        case::plain_imaginary_j("2j", Complex::new(0.0, 2.0)),
+        case::plain_imaginary_j_only("-J", Complex::new(0.0, -1.0)),
+        case::plain_both_parts_j("3 - 0.5j", Complex::new(3.0, -0.5)),
         case::plain_imaginary_both_parts("2 + 3i", Complex::new(2.0, 3.0)),
         case::plain_imaginary_both_parts_real_negative("-2.0 + 3i", Complex::new(-2.0, 3.0)),
         case::plain_imaginary_both_parts_imaginary_negative("2 - 3.4i", Complex::new(2.0, -3.4)),
@@ -174,7 +469,8 @@ mod tests {
         case::polar_whitespace_ok("  @   {1, 0   }  ", Complex::new(1.0, 0.0)),
         case::polar_exponents("@{200.0e-2, 1.0E1}", Complex::new(-1.678143, -1.088042)),
         case::plain_with_brackets("{2 + 4i}", Complex::new(2.0, 4.0)),
-        case::plain_with_brackets("{2 + i}", Complex::new(2.0, 1.0))
+        case::plain_with_brackets("{2 + i}", Complex::new(2.0, 1.0)),
+        case::plain_with_brackets_j("{1 + 4j}", Complex::new(1.0, 4.0))
     )]
     fn from_str_works(input: &str, expected: Complex) {
         let result = from_str(input).unwrap();
@@ -210,4 +506,112 @@ mod tests {
         let result = from_str(input);
         assert!(result.is_err());
     }
+
+    #[rstest(
+        input, format, position,
+        case::blank("", LiteralFormat::Standard, 0),
+        case::not_a_number("fgdfgdfg", LiteralFormat::Standard, 0),
+        case::no_content("{}", LiteralFormat::BracketedStandard, 1),
+        case::no_brackets("1.0, -1.0", LiteralFormat::Standard, 3),
+        case::missing_end_bracket("{1.0, 1.0", LiteralFormat::Bracket, 9),
+        case::missing_comma("{1.0 2.0}", LiteralFormat::BracketedStandard, 5),
+        case::too_many_parts("{1.0, 2.0, 3.0}", LiteralFormat::Bracket, 9),
+        case::first_not_a_number("{b, 1.0}", LiteralFormat::Bracket, 1),
+        case::plain_imaginary_not_a_number("2 - wi", LiteralFormat::Standard, 4),
+        case::plain_too_many_parts("2 + 4 + 3i", LiteralFormat::Standard, 6),
+        case::plain_imaginary_first("3i + 2", LiteralFormat::Standard, 3),
+        case::bad_exponent("{1e+ 2}", LiteralFormat::BracketedStandard, 4),
+        case::polar_no_brackets("@", LiteralFormat::Polar, 1),
+        case::polar_too_few_parts("  @{1}", LiteralFormat::Polar, 5),
+        case::polar_trailing("@{1, 2} x", LiteralFormat::Polar, 8),
+        case::multibyte_offset("{1, 2θ}", LiteralFormat::Bracket, 5),
+        case::polar_bad_unit("@{1, 2turn}", LiteralFormat::Polar, 6),
+        case::phasor_no_angle("10∠", LiteralFormat::Phasor, 5),
+        case::phasor_unclosed("{10∠45°", LiteralFormat::Phasor, 10),
+        case::phasor_trailing("10∠45° 5", LiteralFormat::Phasor, 10)
+    )]
+    fn parse_error_has_format_and_position(input: &str, format: LiteralFormat, position: usize) {
+        let expected = ComplexError::ParseError { input: input.to_owned(), format, position };
+        assert_eq!(Err(expected), from_str(input));
+    }
+
+    #[test]
+    fn from_str_trait() {
+        let result: Complex = "{3 - 2i}".parse().unwrap();
+        assert_eq!(Complex::new(3.0, -2.0), result);
+    }
+
+    #[test]
+    fn try_from_str() {
+        let result = Complex::try_from("{3, -2}").unwrap();
+        assert_eq!(Complex::new(3.0, -2.0), result);
+    }
+
+    #[rstest(
+        input, expected,
+        case::polar("@{2, -0.5}", PolarComplex::new(2.0, -0.5)),
+        case::polar_unnormalised_angle("@{2, 10}", PolarComplex::new(2.0, 10.0)),
+        case::standard("-2", PolarComplex::new(2.0, std::f64::consts::PI)),
+        case::bracket("{0, 3}", PolarComplex::new(3.0, std::f64::consts::FRAC_PI_2)),
+        case::polar_radians("@{2, 1.5rad}", PolarComplex::new(2.0, 1.5)),
+        case::polar_degrees("@{10, 45deg}", PolarComplex::from_degrees(10.0, 45.0)),
+        case::polar_degree_sign("@{10, -90°}", PolarComplex::from_degrees(10.0, -90.0)),
+        case::polar_gradians("@{10, 50 grad}", PolarComplex::from_gradians(10.0, 50.0)),
+        case::phasor("10∠45°", PolarComplex::from_degrees(10.0, 45.0)),
+        case::phasor_radians("2.5∠-1", PolarComplex::new(2.5, -1.0)),
+        case::phasor_bracketed("{ 10 ∠ 30deg }", PolarComplex::from_degrees(10.0, 30.0))
+    )]
+    fn polar_from_str(input: &str, expected: PolarComplex) {
+        let result: PolarComplex = input.parse().unwrap();
+        assert_eq!(expected, result);
+    }
+
+    #[rstest(
+        input, expected,
+        case::polar("@{1, 90}", Complex::new(0.0, 1.0)),
+        case::phasor("2∠-180", Complex::new(-2.0, 0.0)),
+        case::suffix_overrides("@{1, 3.14159265358979323846rad}", Complex::new(-1.0, 0.0))
+    )]
+    fn degrees_by_default(input: &str, expected: Complex) {
+        set_angle_unit(AngleUnit::Degrees);
+        let result = from_str(input);
+        set_angle_unit(AngleUnit::Radians);
+
+        assert_eq!(Ok(expected), result);
+    }
+
+    #[test]
+    fn polar_try_from_str_error() {
+        let result = PolarComplex::<f64>::try_from("@{2}");
+        let expected = ComplexError::ParseError { input: "@{2}".to_owned(), format: LiteralFormat::Polar, position: 3 };
+        assert_eq!(Err(expected), result);
+    }
+
+    #[test]
+    fn f32_from_str() {
+        let result: Complex<f32> = "{0.1 - 2.5i}".parse().unwrap();
+        assert_eq!(Complex::new(0.1_f32, -2.5_f32), result);
+    }
+
+    #[rstest(
+        input, expected,
+        case::bracket("{3, -4}", Complex::new(3, -4)),
+        case::standard("-2 + 7i", Complex::new(-2, 7)),
+        case::bracketed_standard("{-i}", Complex::new(0, -1))
+    )]
+    fn integer_from_str(input: &str, expected: Complex<i64>) {
+        assert_eq!(expected, input.parse().unwrap());
+    }
+
+    #[rstest(
+        input, format, position,
+        case::decimal("{1.5, 2}", LiteralFormat::Bracket, 1),
+        case::exponent("2 + 1e3i", LiteralFormat::Standard, 4),
+        case::polar(" @{1, 0}", LiteralFormat::Polar, 1),
+        case::phasor("1∠90°", LiteralFormat::Phasor, 0)
+    )]
+    fn integer_parse_error(input: &str, format: LiteralFormat, position: usize) {
+        let expected = ComplexError::ParseError { input: input.to_owned(), format, position };
+        assert_eq!(Err(expected), input.parse::<Complex<i32>>());
+    }
 }
@@ -0,0 +1,474 @@
+use crate::domain::Domain;
+use crate::error::{ComplexError, LocatedError};
+use crate::expr::{BinaryOp, Expr, Statement, UnaryOp};
+use crate::span::Span;
+use crate::tokenize::{tokenize_spanned, Token};
+
+// How tightly a prefix operator holds the expression after it. Unary signs
+// sit below `^`, so that -{2}^{2} is -4, and `~` above it, whether it comes
+// before its operand or after it.
+const SIGN_POWER: u8 = 5;
+const CONJUGATE_POWER: u8 = 9;
+
+// The deepest that brackets, prefix operators, arguments and `^` may nest,
+// which keeps parsing, evaluating and printing the tree well within the stack.
+const MAX_DEPTH: usize = 256;
+
+/// Parse an expression such as `sin({1 + i}) * -{2}` into a tree, with the
+/// usual precedence of operators and `^` grouping to the right. Errors are
+/// located at the part of the input they are about.
+pub fn parse<N: Domain>(input: &str) -> Result<Expr<N>, LocatedError> {
+    let mut parser = Parser::new(input)?;
+    let expr = parser.expression(0)?;
+    parser.end()?;
+
+    Ok(expr)
+}
+
+/// Parse a line of shell input, which is an expression or, when it starts
+/// with a name and `=`, an assignment, or with a call whose arguments are all
+/// names and then `=`, a function definition.
+pub fn parse_statement<N: Domain>(input: &str) -> Result<Statement<N>, LocatedError> {
+    let mut parser = Parser::new(input)?;
+
+    let statement = if let Some((name, params, next)) = parser.definition() {
+        if let Some(i) = (0..params.len()).find(|i| params[..*i].contains(&params[*i])) {
+            // the parameters are every other token from the third
+            let message = format!("parameter '{}' of '{}' is given twice", params[i], name);
+            return Err(ComplexError::SyntaxError(message).at(parser.tokens[2 + 2 * i].0));
+        }
+
+        parser.next = next;
+        Statement::Define(name, params, parser.expression(0)?)
+    } else if let [(_, Token::Identifier(name)), (_, Token::Equals), ..] = parser.tokens.as_slice() {
+        let name = name.clone();
+        parser.next = 2;
+        Statement::Assign(name, parser.expression(0)?)
+    } else {
+        Statement::Expression(parser.expression(0)?)
+    };
+    parser.end()?;
+
+    Ok(statement)
+}
+
+// The binding powers on the left and right of an infix operator. A left
+// power below the right one groups to the left.
+fn binding_power(op: BinaryOp) -> (u8, u8) {
+    match op {
+        BinaryOp::Add | BinaryOp::Subtract => (1, 2),
+        BinaryOp::Multiply | BinaryOp::Divide => (3, 4),
+        BinaryOp::Power => (8, 7),
+    }
+}
+
+fn infix<N>(token: &Token<N>) -> Option<BinaryOp> {
+    match token {
+        Token::Plus => Some(BinaryOp::Add),
+        Token::Minus => Some(BinaryOp::Subtract),
+        Token::Multiply => Some(BinaryOp::Multiply),
+        Token::Divide => Some(BinaryOp::Divide),
+        Token::Power => Some(BinaryOp::Power),
+        _ => None,
+    }
+}
+
+// The token as the user typed it, for error messages.
+fn describe<N: Domain>(token: &Token<N>) -> String {
+    match token {
+        Token::UnaryPlus => "+".to_string(),
+        Token::UnaryMinus => "-".to_string(),
+        _ => token.to_string(),
+    }
+}
+
+struct Parser<'a, N> {
+    line: &'a str,
+    tokens: Vec<(Span, Token<N>)>,
+    next: usize,
+    depth: usize,
+}
+
+impl<'a, N: Domain> Parser<'a, N> {
+    fn new(line: &'a str) -> Result<Self, LocatedError> {
+        let tokens = tokenize_spanned(line)?;
+
+        if tokens.is_empty() {
+            return Err(ComplexError::SyntaxError("Empty expression".to_string()).at(Span::new(line.len(), line.len())));
+        }
+
+        Ok(Parser { line, tokens, next: 0, depth: 0 })
+    }
+
+    // The name and parameters of a function definition such as
+    // `f(z, w) = ...`, with the index of the token after the `=`.
+    fn definition(&self) -> Option<(String, Vec<String>, usize)> {
+        let [(_, Token::Identifier(name)), (_, Token::LeftParen), rest @ ..] = self.tokens.as_slice() else {
+            return None;
+        };
+
+        let mut rest = rest.iter();
+        let mut params = Vec::new();
+
+        loop {
+            match rest.next()? {
+                (_, Token::Identifier(param)) => params.push(param.clone()),
+                (_, Token::RightParen) if params.is_empty() => break,
+                _ => return None,
+            }
+
+            match rest.next()? {
+                (_, Token::Comma) => {}
+                (_, Token::RightParen) => break,
+                _ => return None,
+            }
+        }
+
+        match rest.next()? {
+            (_, Token::Equals) => Some((name.clone(), params, self.tokens.len() - rest.len())),
+            _ => None,
+        }
+    }
+
+    // Check that every token has been used.
+    fn end(&mut self) -> Result<(), LocatedError> {
+        match self.advance() {
+            None => Ok(()),
+            Some((span, Token::RightParen)) => Err(self.unmatched(")", span)),
+            Some((span, token)) => Err(self.unexpected(&token, span)),
+        }
+    }
+
+    fn peek(&self) -> Option<&(Span, Token<N>)> {
+        self.tokens.get(self.next)
+    }
+
+    fn advance(&mut self) -> Option<(Span, Token<N>)> {
+        let token = self.tokens.get(self.next).cloned();
+        self.next += 1;
+        token
+    }
+
+    // An operand followed by any infix operators that bind at least as
+    // tightly as `min_power`.
+    fn expression(&mut self, min_power: u8) -> Result<Expr<N>, LocatedError> {
+        let depth = self.depth;
+        let expr = self.nested_expression(min_power);
+        self.depth = depth;
+
+        expr
+    }
+
+    fn nested_expression(&mut self, min_power: u8) -> Result<Expr<N>, LocatedError> {
+        self.nest()?;
+        let mut left = self.operand()?;
+
+        while let Some((span, token)) = self.peek() {
+            if matches!(token, Token::Conjugate) {
+                if CONJUGATE_POWER < min_power {
+                    break;
+                }
+
+                self.nest()?;
+                self.next += 1;
+                left = Expr::Unary(UnaryOp::Conjugate, Box::new(left));
+                continue;
+            }
+
+            let op = match infix(token) {
+                Some(op) => op,
+                None if matches!(token, Token::RightParen | Token::Comma) => break,
+                None => return Err(self.unexpected(token, *span)),
+            };
+
+            let (left_power, right_power) = binding_power(op);
+            if left_power < min_power {
+                break;
+            }
+
+            self.next += 1;
+            let right = self.expression(right_power)?;
+            left = Expr::Binary(op, Box::new(left), Box::new(right));
+        }
+
+        Ok(left)
+    }
+
+    fn operand(&mut self) -> Result<Expr<N>, LocatedError> {
+        let Some((span, token)) = self.advance() else {
+            return Err(self.missing_operand());
+        };
+
+        match token {
+            Token::ComplexNumber(c) => Ok(Expr::Number(c)),
+            Token::Identifier(name) => match self.peek() {
+                Some((bracket, Token::LeftParen)) => {
+                    let bracket = *bracket;
+                    self.next += 1;
+                    Ok(Expr::Call(name, self.arguments(bracket)?))
+                }
+                _ => Ok(Expr::Variable(name)),
+            },
+            Token::LeftParen => {
+                let expr = self.expression(0)?;
+                self.close(span)?;
+
+                Ok(expr)
+            }
+            Token::UnaryPlus | Token::UnaryMinus | Token::Conjugate => {
+                let (op, power) = match token {
+                    Token::UnaryPlus => (UnaryOp::Plus, SIGN_POWER),
+                    Token::UnaryMinus => (UnaryOp::Minus, SIGN_POWER),
+                    _ => (UnaryOp::Conjugate, CONJUGATE_POWER),
+                };
+
+                Ok(Expr::Unary(op, Box::new(self.expression(power)?)))
+            }
+            Token::RightParen => {
+                self.next -= 1;
+                Err(self.missing_operand())
+            }
+            _ => Err(self.unexpected(&token, span)),
+        }
+    }
+
+    // Go a level deeper into the tree, which is an error at the next token
+    // past the limit.
+    fn nest(&mut self) -> Result<(), LocatedError> {
+        self.depth += 1;
+        if self.depth <= MAX_DEPTH {
+            return Ok(());
+        }
+
+        let (span, _) = &self.tokens[self.next.min(self.tokens.len() - 1)];
+        Err(ComplexError::SyntaxError(format!("expression nests too deeply at column {}", span.column(self.line))).at(*span))
+    }
+
+    fn unexpected(&self, token: &Token<N>, span: Span) -> LocatedError {
+        ComplexError::SyntaxError(format!("unexpected token '{}' at column {}", describe(token), span.column(self.line)))
+            .at(span)
+    }
+
+    fn unmatched(&self, bracket: &str, span: Span) -> LocatedError {
+        ComplexError::SyntaxError(format!("unmatched '{}' at column {}", bracket, span.column(self.line))).at(span)
+    }
+
+    // The error for an operand that is not there, which names the token it
+    // should have followed.
+    fn missing_operand(&self) -> LocatedError {
+        match self.tokens[..self.next.min(self.tokens.len())].last() {
+            Some((span, token)) => ComplexError::SyntaxError(format!(
+                "missing operand after '{}' at column {}",
+                describe(token),
+                span.column(self.line)
+            ))
+            .at(*span),
+            None => ComplexError::SyntaxError("Empty expression".to_string()).at(Span::new(0, 0)),
+        }
+    }
+
+    // The arguments of a call, after the bracket in the span, up to and
+    // including the closing bracket.
+    fn arguments(&mut self, bracket: Span) -> Result<Vec<Expr<N>>, LocatedError> {
+        let mut args = Vec::new();
+
+        if matches!(self.peek(), Some((_, Token::RightParen))) {
+            self.next += 1;
+            return Ok(args);
+        }
+
+        loop {
+            args.push(self.expression(0)?);
+
+            match self.peek() {
+                Some((_, Token::Comma)) => self.next += 1,
+                _ => break,
+            }
+        }
+
+        self.close(bracket)?;
+
+        Ok(args)
+    }
+
+    // Take the bracket that closes the one in the span.
+    fn close(&mut self, bracket: Span) -> Result<(), LocatedError> {
+        match self.advance() {
+            Some((_, Token::RightParen)) => Ok(()),
+            Some((span, token)) => Err(self.unexpected(&token, span)),
+            None => Err(self.unmatched("(", bracket)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::complex::{ArithmeticMode, Complex};
+    use crate::environment::Environment;
+    use rstest::rstest;
+
+    fn parsed(input: &str) -> Expr {
+        parse(input).unwrap()
+    }
+
+    // Whether `a op1 b op2 c` groups as `(a op1 b) op2 c`, marked L, or as
+    // `a op1 (b op2 c)`, marked R. Rows are op1 and columns op2.
+    #[test]
+    fn precedence_matrix() {
+        let ops = ["+", "-", "*", "/", "^"];
+        let matrix = [
+            "LLRRR",
+            "LLRRR",
+            "LLLLR",
+            "LLLLR",
+            "LLLLR",
+        ];
+
+        for (op1, row) in ops.iter().zip(matrix) {
+            for (op2, grouping) in ops.iter().zip(row.chars()) {
+                let input = format!("{{2}} {op1} {{3}} {op2} {{5}}");
+                let expected = match grouping {
+                    'L' => format!("({{2}} {op1} {{3}}) {op2} {{5}}"),
+                    _ => format!("{{2}} {op1} ({{3}} {op2} {{5}})"),
+                };
+
+                assert_eq!(parsed(&expected), parsed(&input), "{input}");
+            }
+        }
+    }
+
+    #[rstest(
+        input, expected,
+        case::negate_power("-{2}^{2}", "-({2}^{2})"),
+        case::power_of_negative("{2}^-{2}", "{2}^(-{2})"),
+        case::negate_product("-{2} * +{3}", "(-{2}) * (+{3})"),
+        case::conjugate_power("~{i}^{2}", "(~{i})^{2}"),
+        case::power_of_conjugate("{2}^~{i}", "{2}^(~{i})"),
+        case::conjugate_negative("~-{i}", "~(-{i})"),
+        case::postfix_conjugate("{i}~", "~{i}"),
+        case::postfix_conjugate_power("{i}^{2}~", "{i}^(~{2})"),
+        case::negate_postfix_conjugate("-{i}~", "-(~{i})"),
+        case::product_postfix_conjugate("{2} * {i}~", "{2} * (~{i})"),
+        case::subtract_negative("{1} - -{2}", "{1} - (-{2})"),
+        case::power_chain("{2}^{3}^{2}", "{2}^({3}^{2})"),
+        case::difference_chain("{1} - {2} - {3}", "({1} - {2}) - {3}"),
+        case::quotient_chain("{1} / {2} / {3}", "({1} / {2}) / {3}"),
+        case::function_operand("{1} + sin({2}) * {3}", "{1} + (sin({2}) * {3})"),
+        case::bracketed("({1} + {2}) * {3}", "({1} + {2}) * {3}"),
+        case::nested_arguments("pow({1} + {1}, ({2}))", "pow(({1} + {1}), {2})"),
+        case::bare_numbers("2 + 3i * 4", "{2} + ({3i} * {4})")
+    )]
+    fn grouping(input: &str, expected: &str) {
+        assert_eq!(parsed(expected), parsed(input));
+    }
+
+    #[rstest(
+        input, expected,
+        case::empty("", "Empty expression"),
+        case::blank("   ", "Empty expression"),
+        case::trailing_operator("{1} +", "missing operand after '+' at column 5"),
+        case::lone_minus("-", "missing operand after '-' at column 1"),
+        case::empty_brackets("()", "missing operand after '(' at column 1"),
+        case::operand_before_bracket("({1} *)", "missing operand after '*' at column 6"),
+        case::double_operator("{1} + * {2}", "unexpected token '*' at column 7"),
+        case::adjacent_operands("{1} {2}", "unexpected token '{2}' at column 5"),
+        case::comma("{1}, {2}", "unexpected token ',' at column 4"),
+        case::unclosed("({1} + {2}", "unmatched '(' at column 1"),
+        case::unopened("{1} + {2})", "unmatched ')' at column 10"),
+        case::unclosed_inner("(({1}) + {2}", "unmatched '(' at column 1"),
+        case::unclosed_function("{1} + sin({1}", "unmatched '(' at column 10"),
+        case::in_argument("sin({1} +)", "missing operand after '+' at column 9"),
+        case::in_second_argument("pow({1}, * {2})", "unexpected token '*' at column 10"),
+        case::in_spaced_call("sqrt ({2} {3})", "unexpected token '{3}' at column 11")
+    )]
+    fn errors(input: &str, expected: &str) {
+        assert_eq!(Err(ComplexError::SyntaxError(expected.to_owned())), parse::<Complex>(input).map_err(|e| e.error));
+    }
+
+    #[rstest(
+        input, column,
+        case::signs(format!("{}{{1}}", "-".repeat(5000)), 257),
+        case::brackets(format!("{}{{1}}{}", "(".repeat(5000), ")".repeat(5000)), 257),
+        case::powers(vec!["{1}"; 5000].join("^"), 1025),
+        case::arguments(format!("{}{{1}}{}", "sin(".repeat(5000), ")".repeat(5000)), 1025),
+        case::postfix_conjugates(format!("{{1}}{}", "~".repeat(5000)), 259)
+    )]
+    fn too_deep(input: String, column: usize) {
+        let error = parse::<Complex>(&input).unwrap_err();
+        assert_eq!(Some(column), Some(error.span.column(&input)));
+    }
+
+    #[test]
+    fn deep_but_allowed() {
+        let input = format!("{}{{1}}", "-".repeat(255));
+        let expr = parsed(&input);
+        assert_eq!(input, expr.to_string());
+        assert!(expr.evaluate(&Environment::new(), ArithmeticMode::Strict).is_ok());
+    }
+
+    #[rstest(
+        input, span,
+        case::trailing_operator("{1} +", Span::new(4, 5)),
+        case::unexpected_number("{1} {2 + i}", Span::new(4, 11)),
+        case::unopened("({1}))", Span::new(5, 6)),
+        case::in_argument("sin({1} *)", Span::new(8, 9)),
+        case::after_wide_characters("2∠90° ) 1", Span::new(9, 10)),
+        case::literal_in_argument("pow({1}, {2 + 3x})", Span::new(15, 16))
+    )]
+    fn error_spans(input: &str, span: Span) {
+        assert_eq!(Some(span), Some(parse::<Complex>(input).unwrap_err().span));
+    }
+
+    #[rstest(
+        input, expected,
+        case::expression("z * {2}", Statement::Expression(Expr::Binary(
+            BinaryOp::Multiply,
+            Box::new(Expr::Variable("z".to_owned())),
+            Box::new(Expr::Number(Complex::new(2.0, 0.0)))
+        ))),
+        case::assignment("w = ~z", Statement::Assign(
+            "w".to_owned(),
+            Expr::Unary(UnaryOp::Conjugate, Box::new(Expr::Variable("z".to_owned())))
+        )),
+        case::call_with_no_arguments("f()", Statement::Expression(Expr::Call("f".to_owned(), vec![]))),
+        case::definition("f(z) = z^2", Statement::Define(
+            "f".to_owned(),
+            vec!["z".to_owned()],
+            Expr::Binary(BinaryOp::Power, Box::new(Expr::Variable("z".to_owned())), Box::new(Expr::Number(Complex::new(2.0, 0.0))))
+        )),
+        case::definition_with_parameters("g(z, w) = w", Statement::Define(
+            "g".to_owned(),
+            vec!["z".to_owned(), "w".to_owned()],
+            Expr::Variable("w".to_owned())
+        )),
+        case::constant_function("c() = {1}", Statement::Define("c".to_owned(), vec![], Expr::Number(Complex::new(1.0, 0.0))))
+    )]
+    fn statement(input: &str, expected: Statement) {
+        assert_eq!(Ok(expected), parse_statement(input));
+    }
+
+    #[rstest(
+        input, expected,
+        case::no_value("z =", "missing operand after '=' at column 3"),
+        case::not_a_name("{1} = {2}", "unexpected token '=' at column 5"),
+        case::twice("z = w = {1}", "unexpected token '=' at column 7"),
+        case::empty_argument("pow({1},)", "missing operand after ',' at column 8"),
+        case::no_body("f(z) =", "missing operand after '=' at column 6"),
+        case::number_parameter("f({1}) = {2}", "unexpected token '=' at column 8"),
+        case::expression_parameter("f(z + w) = z", "unexpected token '=' at column 10"),
+        case::repeated_parameter("f(z, z) = z", "parameter 'z' of 'f' is given twice")
+    )]
+    fn statement_errors(input: &str, expected: &str) {
+        assert_eq!(Err(ComplexError::SyntaxError(expected.to_owned())), parse_statement::<Complex>(input).map_err(|e| e.error));
+    }
+
+    #[rstest(
+        input, span,
+        case::repeated_parameter("f(z, w, z) = z", Span::new(8, 9)),
+        case::empty("  ", Span::new(2, 2))
+    )]
+    fn statement_error_spans(input: &str, span: Span) {
+        assert_eq!(span, parse_statement::<Complex>(input).unwrap_err().span);
+    }
+}
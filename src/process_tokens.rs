@@ -1,112 +1,211 @@
-use crate::complex::Complex;
-
-use crate::tokenize::Token;
-
-pub fn process(tokens: &mut Vec<Token>) -> Result<Complex, String> {
-    match process_recursively(tokens) {
-        Ok(Token::ComplexNumber(c)) => {
-            Ok(c)
-        },
-        Err(e) => {
-            Err(e)
-        },
-        _ => {
-            Err("recursive processing returned a non-complex number token".to_string())
-        }
-    }
-}
-
-fn process_recursively(tokens: &mut Vec<Token>) -> Result<Token, String> {
-    if tokens.len() == 1 {
-        match tokens.pop() {
-            Some(token @ Token::ComplexNumber(_)) => {
-                Ok(token)
-            },
-            _ => {
-                Err("Single remaining token must be a complex number".to_string())
-            }
-        }
-    } else {
-        // evaluate tokens down to a final number
-        let mut i = 0;
-        while i < tokens.len() {
-            match &tokens[i] {
-                token @ (Token::Plus | Token::Minus | Token::Multiply | Token::Divide | Token::Power) => {
-                    if i < 2 {
-                        return Err("Need two operands for + = * - or ^".to_string());
-                    }
-
-                    let left = extract_complex(&tokens[i - 2])?;
-                    let right = extract_complex(&tokens[i - 1])?;
-
-                    let result = match token {
-                        Token::Plus => left.add(right),
-                        Token::Minus => left.sub(right),
-                        Token::Divide => left.div(right),
-                        Token::Multiply => left.mul(right),
-                        Token::Power => left.pow(right.re),
-                        _ => panic!("Impossible operator")
-                    };
-
-                    let result_token = Token::ComplexNumber(result.unwrap());
-
-                    tokens.splice(i - 2..=i, [result_token]);
-
-                    i = 0;
-                },/* 
-                Token::Power => {
-                    if i < 1 {
-                        return Err("Need to operands before ^".to_string());
-                    }
-
-                    let operand = extract_complex(&tokens[i - 1])?;
-
-                    let result  operand.
-                },*/
-                _ => {
-                    i += 1;
-                }
-            }
-        }
-
-        Ok(tokens.pop().unwrap())
-
-        //Err("Not yet implemented".to_string())
-
-    }
+use crate::complex::ArithmeticMode;
+use crate::domain::Domain;
+use crate::environment::Environment;
+use crate::error::ComplexError;
+use crate::expr::Expr;
+use crate::value::Value;
+
+/// Parse and evaluate an expression, with numbers in the domain `N`.
+pub fn process<N: Domain>(input: &str) -> Result<Value<N>, ComplexError> {
+    process_in(input, ArithmeticMode::Strict)
 }
 
-fn extract_complex(token: &Token) -> Result<Complex, String> {
-    match token {
-        Token::ComplexNumber(c) => Ok(*c),
-        _ => Err("Token does not contain a complex number".to_string())
-    }
+/// `process` under the given arithmetic mode, which in `Ieee` mode lets
+/// operators produce infinities and NaNs rather than errors.
+pub fn process_in<N: Domain>(input: &str, mode: ArithmeticMode) -> Result<Value<N>, ComplexError> {
+    Expr::parse(input)?.evaluate(&Environment::new(), mode)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::bigfloat::BigFloat;
+    use crate::complex::Complex;
+    use crate::gaussian::GaussianInt;
+    use crate::rational::RationalComplex;
     use rstest::rstest;
-    use crate::tokenize::{shunting_yard, tokenize};
 
     #[rstest(
         input, expected,
         case::single_number("{2 + i}", "{2 + i}"),
-        case::plus("{2} + {i}}", "{2 + i}"),
+        case::plus("{2} + {i}", "{2 + i}"),
         case::double_plus("{2} + {i} + {2 + i}", "{4 + 2i}"),
         case::subtract("{3 + i} - {1 - i}", "{2 + 2i}"),
         case::multiple_plus("{2} + {i} + {-2} + {-i} + {3 - 3i} + {-3 + 3i}", "{0}"),
         case::multiply("{i} * {i}", "{-1}"),
         case::multiple_more("{1 + i} * {3 - i}", "{4 + 2i}"),
-        case::divide("{4 + 2i} / {3 - i}", "{1 + i}")
+        case::divide("{4 + 2i} / {3 - i}", "{1 + i}"),
+        case::function("sin({0})", "{0}"),
+        case::function_nested("exp(ln({2 + i}))", "{2 + i}"),
+        case::function_expression_argument("sqrt({2} * {2})", "{2}"),
+        case::function_in_expression("{1} + cos({0}) * {2}", "{3}"),
+        case::function_spaced_name("sqrt ({-4})", "{2i}"),
+        case::roots("roots({-8}, {3})", "[{1 + 1.732051i}, {-2}, {1 - 1.732051i}]"),
+        case::roots_of_expression("roots({2} * {2}, {1} + {1})", "[{2}, {-2}]"),
+        case::phasor("10∠90° * {2}", "{20i}"),
+        case::polar_degrees("@{2, 180deg} + {1}", "{-1}"),
+        case::phasor_gradians("{4∠-100grad} / {2}", "{-2i}"),
+        case::series_rlc("zr({10}) + zl({0.1}, {50}) + zc({100e-6}, {50})", "{10 - 0.415062i}"),
+        case::parallel("par(zr({100}), zc({1e-6}, {1591.549431}))", "{50 - 50i}"),
+        case::divider_current("vdiv(10∠0°, zr({1000}), zl({1}, {159.154943})) / zl({1}, {159.154943})", "{0.005 - 0.005i}"),
+        case::conjugate("~{1 + 2i}", "{1 - 2i}"),
+        case::conjugate_product("~{1 + i} * {1 + i}", "{2}"),
+        case::conjugate_bracket("~({1 + i} * {i})", "{-1 - i}"),
+        case::double_conjugate("~~{3 - i}", "{3 - i}"),
+        case::postfix_conjugate("{1+2i}~", "{1 - 2i}"),
+        case::postfix_conjugate_call("sqrt({-4})~ * {2}", "{-4i}"),
+        case::postfix_conjugate_then_minus("{1 + i}~ - {1}", "{-i}"),
+        case::conjugate_both_sides("~{3 - i}~", "{3 - i}"),
+        case::negate_postfix_conjugate("-{1 + i}~", "{-1 + i}"),
+        case::abs("abs({3 + 4i})", "{5}"),
+        case::arg("arg({-1})", "{3.141593}"),
+        case::re("re({3 - 2i})", "{3}"),
+        case::im("im({3 - 2i})", "{-2}"),
+        case::norm("norm({3 + 4i})", "{25}"),
+        case::sign("sign({3 + 4i})", "{0.6 + 0.8i}"),
+        case::sign_zero("sign({0})", "{0}"),
+        case::modulus_of_conjugate("abs(~{-5 - 12i}) + re(~{2i})", "{13}"),
+        case::negate("-{2}", "{-2}"),
+        case::plus_sign("+{2}", "{2}"),
+        case::negate_imaginary("{1} * -{i}", "{-i}"),
+        case::negate_power("-{2}^{2}", "{-4}"),
+        case::power_of_negative("{2}^-{1}", "{0.5}"),
+        case::negate_bracket("-({2}^{2})", "{-4}"),
+        case::bracket_negative_power("(-{2})^{2}", "{4}"),
+        case::double_negative("{1} - -{1}", "{2}"),
+        case::nested_signs("-+-{3}", "{3}"),
+        case::negate_in_bracket("{2} * (-{1} + {4})", "{6}"),
+        case::negate_function("-sqrt({4}) * -{1}", "{2}"),
+        case::negate_argument("sqrt(-{4})", "{2i}"),
+        case::negate_conjugate("-~{1 + i}", "{-1 + i}"),
+        case::bare("2 + 3i", "{2 + 3i}"),
+        case::bare_j("(1 + 2j) * (1 - 2j)", "{5}"),
+        case::bare_exponent("3.5e-2 * 100", "{3.5}"),
+        case::bare_negative_power("-2^2 + 4i^2", "{-20}"),
+        case::bare_argument("sqrt(-4) + zr(50)", "{50 + 2i}"),
+        case::bare_and_braced("{1 + i} * 2 - @{2, 0}", "{2i}"),
+        case::complex_power("{i}^{i}", "{0.20788}"),
+        case::fractional_power("{-4}^{0.5}", "{2i}"),
+        case::subtract_then_add("{5} - {1} + {1}", "{5}"),
+        case::divide_then_multiply("{8} / {2} * {2}", "{8}"),
+        case::subtract_chain("{10} - {3} - {2}", "{5}"),
+        case::power_chain("{2}^{3}^{2}", "{512}")
     )]
     fn test_processing(input: &str, expected: &str) {
-        let tokenized = tokenize(input).unwrap();
-        let mut shunted = shunting_yard(tokenized);
+        let result = process::<Complex>(input).unwrap();
+
+        assert_eq!(expected, result.to_string());
+    }
+
+    // whole number powers are exact, rather than going by way of polar form
+    #[rstest(
+        input, expected,
+        case::eighth("{1 + i}^{8}", Complex::new(16.0, 0.0)),
+        case::negative("{1 + i}^{-2}", Complex::new(0.0, -0.5)),
+        case::large("{1.5}^{20}", Complex::new(3325.256730079651, 0.0)),
+        case::cube("{3 - 2i}^{3}", Complex::new(-9.0, -46.0))
+    )]
+    fn test_processing_whole_power(input: &str, expected: Complex) {
+        assert_eq!(Ok(Value::Number(expected)), process(input));
+    }
 
-        let result = process(&mut shunted).unwrap();
+    #[rstest(
+        input, expected,
+        case::unknown_function("nope({1})", ComplexError::UnknownFunction("nope".to_owned())),
+        case::unknown_variable("x + {1}", ComplexError::UnknownVariable("x".to_owned())),
+        case::wrong_arity("sin({1}, {2})", ComplexError::ArityMismatch { name: "sin".to_owned(), expected: 1, found: 2 }),
+        case::not_finite("ln({0})", ComplexError::DivisionByZero),
+        case::divide_by_zero("{1} / {0}", ComplexError::DivisionByZero),
+        case::overflow("{1e300} * {1e300}", ComplexError::Overflow),
+        case::missing_operand("{1} +", ComplexError::SyntaxError("missing operand after '+' at column 5".to_owned())),
+        case::lone_minus("-", ComplexError::SyntaxError("missing operand after '-' at column 1".to_owned())),
+        case::conjugate_of_vector("~roots({4}, {2})", ComplexError::InvalidArgument("'roots({4}, {2})' is a vector, not a number".to_owned())),
+        case::vector_operand("roots({4}, {2}) + {1}", ComplexError::InvalidArgument("'roots({4}, {2})' is a vector, not a number".to_owned())),
+        case::vector_argument("sin(roots({4}, {2}))", ComplexError::InvalidArgument("'roots({4}, {2})' is a vector, not a number".to_owned()))
+    )]
+    fn test_processing_errors(input: &str, expected: ComplexError) {
+        assert_eq!(Err(expected), process::<Complex>(input));
+    }
+
+    #[rstest(
+        input, expected,
+        case::divide_by_zero("{1} / {0}", "{inf + NaNi}"),
+        case::negative_by_zero("{-1} / {0}", "{-inf + NaNi}"),
+        case::zero_by_zero("{0} / {0}", "{NaN + NaNi}"),
+        case::overflow("{1e300} * {1e300}", "{inf}"),
+        case::by_infinity("{1 + i} / ({1e300} * {1e300})", "{0}"),
+        case::power_of_zero("{0}^{-1}", "{inf}"),
+        case::inverse_of_infinity("{1} / ({1} / {0})", "{0}"),
+        case::whole_power("{1 + i}^{8}", "{16}"),
+        case::negative_whole_power("{1 + i}^{-2}", "{-0.5i}"),
+        case::log_of_zero("ln({0})", "{-inf}"),
+        case::exp_overflow("exp({1000})", "{inf}"),
+        case::sin_of_infinity("sin({1} / {0})", "{NaN + NaNi}"),
+        case::complex_power_of_zero("{0}^{-1 + i}", "{inf + NaNi}"),
+        case::complex_power("{-1}^{i}", "{0.043214}"),
+        case::negate_infinity("-({1} / {0})", "{-inf + NaNi}"),
+        case::negative_zero("{1} / -{0}", "{-inf + NaNi}"),
+        case::finite("{4 + 2i} / {3 - i}", "{1 + i}")
+    )]
+    fn test_processing_ieee(input: &str, expected: &str) {
+        let result = process_in::<Complex>(input, ArithmeticMode::Ieee).unwrap();
 
         assert_eq!(expected, result.to_string());
     }
 
-}
\ No newline at end of file
+    #[rstest(
+        input, expected,
+        case::multiply("{3 + 2i} * {1 - i}", "{5 - i}"),
+        case::exact_divide("{4 + 2i} / {1 + i}", "{3 - i}"),
+        case::power("{1 + i}^{4}", "{-4}"),
+        case::gcd("gcd({4 + 2i}, {6})", "{2}"),
+        case::factor("factor({30})", "[{-1}, {1 + i}, {1 + i}, {1 + 2i}, {2 + i}, {3}]"),
+        case::divrem("divrem({7 + 2i}, {2 + i})", "[{3 - i}, {i}]"),
+        case::conjugate("~{2 + 3i} * {2 + 3i}", "{13}"),
+        case::negate("-{2 + 3i}^{2}", "{5 - 12i}"),
+        case::bare("(2 + 3i) * 2j", "{-6 + 4i}"),
+        case::inexact_divide("{3} / {2}", "Invalid argument: {3} is not divisible by {2}")
+    )]
+    fn test_processing_exact(input: &str, expected: &str) {
+        let result = process::<GaussianInt>(input);
+
+        match result {
+            Ok(v) => assert_eq!(expected, v.to_string()),
+            Err(e) => assert_eq!(expected, e.to_string()),
+        }
+    }
+
+    #[rstest(
+        input, expected,
+        case::add("{1/3 + 2/7i} + {2/3 - 2/7i}", "{1}"),
+        case::divide("{1} / {3}", "{1/3}"),
+        case::decimal("{0.1} * {3}", "{3/10}"),
+        case::negative_power("{1 + i}^{-2}", "{-1/2i}"),
+        case::norm("norm({1/2 + 1/2i})", "{1/2}"),
+        case::conjugate("~{1/2 + 1/3i}", "{1/2 - 1/3i}"),
+        case::negate("{1/2} * -{1/3}", "{-1/6}"),
+        case::bare("1/3 + 0.25i", "{1/3 + 1/4i}"),
+        case::fractional_power("{2}^{1/2}", "Invalid argument: {1/2} is not a whole number power")
+    )]
+    fn test_processing_rational(input: &str, expected: &str) {
+        let result = process::<RationalComplex>(input);
+
+        match result {
+            Ok(v) => assert_eq!(expected, v.to_string()),
+            Err(e) => assert_eq!(expected, e.to_string()),
+        }
+    }
+
+    #[rstest(
+        input, expected,
+        case::sqrt("sqrt({2})", "{1.4142135623730950488016887242096980785696718753769480732}"),
+        case::divide("{1} / {3}", "{0.3333333333333333333333333333333333333333333333333333333}"),
+        case::multiply("{1 + i} * {1 - i}", "{2}"),
+        case::exp("exp({1})", "{2.718281828459045235360287471352662497757247093699959575}")
+    )]
+    fn test_processing_bigfloat(input: &str, expected: &str) {
+        let result = process::<Complex<BigFloat>>(input).unwrap();
+
+        assert_eq!(expected, result.to_std_string(12, 55));
+    }
+}
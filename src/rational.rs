@@ -0,0 +1,435 @@
+use std::{cmp::Ordering, fmt, ops::{Add, Div, Mul, Neg, Sub}};
+
+use crate::complex::{Complex, Complex64};
+use crate::error::ComplexError;
+use crate::gaussian::GaussianInt;
+use crate::num::Num;
+
+/// An exact fraction with `i128` numerator and denominator. It's always kept
+/// in lowest terms with a positive denominator, so equal values compare equal
+/// part for part, and the numerator is never `i128::MIN`, so every value can
+/// be negated. The `checked_*` methods report overflow, whereas `+`, `-`, `*`
+/// and `/` panic on it, as the integer operators do, and `/` also panics on
+/// division by zero. Negation never panics.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Rational {
+    num: i128,
+    den: i128,
+}
+
+/// A complex number with exact rational parts, e.g. `{1/3 + 2/7i}`.
+pub type RationalComplex = Complex<Rational>;
+
+impl Rational {
+    /// `num / den` in lowest terms, or an error if `den` is zero or the
+    /// numerator in lowest terms would be `i128::MIN`.
+    pub fn new(num: i128, den: i128) -> Result<Self, ComplexError> {
+        if den == 0 {
+            return Err(ComplexError::DivisionByZero);
+        } else if num == 0 {
+            return Ok(Rational::ZERO);
+        }
+
+        // only i128::MIN / i128::MIN has a gcd too big for an i128
+        let Ok(g) = i128::try_from(gcd(num, den)) else {
+            return Ok(Rational::ONE);
+        };
+        let (num, den) = (num / g, den / g);
+
+        if num == i128::MIN {
+            Err(ComplexError::Overflow)
+        } else if den < 0 {
+            Ok(Rational {
+                num: num.checked_neg().ok_or(ComplexError::Overflow)?,
+                den: den.checked_neg().ok_or(ComplexError::Overflow)?,
+            })
+        } else {
+            Ok(Rational { num, den })
+        }
+    }
+
+    /// The integer `n`, which panics if `n` is `i128::MIN`.
+    pub const fn from_integer(n: i128) -> Self {
+        assert!(n != i128::MIN, "i128::MIN is out of range for a Rational");
+        Rational { num: n, den: 1 }
+    }
+
+    pub fn numerator(&self) -> i128 {
+        self.num
+    }
+
+    pub fn denominator(&self) -> i128 {
+        self.den
+    }
+
+    pub fn is_integer(&self) -> bool {
+        self.den == 1
+    }
+
+    /// Divide, returning an error for division by zero or overflow.
+    pub fn checked_div(self, other: Self) -> Result<Self, ComplexError> {
+        self.checked_mul(other.recip()?)
+    }
+
+    /// `1 / self`, or an error if this is zero.
+    pub fn recip(self) -> Result<Self, ComplexError> {
+        Rational::new(self.den, self.num)
+    }
+
+    /// The nearest `f64`.
+    pub fn to_f64(self) -> f64 {
+        self.num as f64 / self.den as f64
+    }
+}
+
+/// Every finite `f64` is a fraction with a power of two denominator, so
+/// conversion is exact. It fails with `Overflow` when the numerator or
+/// denominator don't fit in an `i128`, and for infinities and NaN.
+impl TryFrom<f64> for Rational {
+    type Error = ComplexError;
+
+    fn try_from(f: f64) -> Result<Self, ComplexError> {
+        if f.is_nan() {
+            return Err(ComplexError::NaNProduced);
+        } else if f.is_infinite() {
+            return Err(ComplexError::Overflow);
+        } else if f == 0.0 {
+            return Ok(Rational::ZERO);
+        }
+
+        // f = mantissa * 2^exponent, with an odd mantissa
+        let bits = f.to_bits();
+        let biased = ((bits >> 52) & 0x7ff) as i32;
+        let fraction = (bits & ((1 << 52) - 1)) as i128;
+        let (mut mantissa, mut exponent) = if biased == 0 {
+            (fraction, -1074)
+        } else {
+            (fraction | (1 << 52), biased - 1075)
+        };
+        let zeros = mantissa.trailing_zeros() as i32;
+        mantissa >>= zeros;
+        exponent += zeros;
+
+        let mantissa = if f < 0.0 { -mantissa } else { mantissa };
+        if exponent >= 0 {
+            let scale = 1i128.checked_shl(exponent as u32).filter(|s| *s > 0).ok_or(ComplexError::Overflow)?;
+            Ok(Rational::from_integer(mantissa.checked_mul(scale).ok_or(ComplexError::Overflow)?))
+        } else {
+            let den = 1i128.checked_shl(-exponent as u32).filter(|s| *s > 0).ok_or(ComplexError::Overflow)?;
+            Rational::new(mantissa, den)
+        }
+    }
+}
+
+impl From<i64> for Rational {
+    fn from(n: i64) -> Self {
+        Rational::from_integer(n as i128)
+    }
+}
+
+impl Num for Rational {
+    const ZERO: Self = Rational::from_integer(0);
+    const ONE: Self = Rational::from_integer(1);
+    const MINUS_ONE: Self = Rational::from_integer(-1);
+
+    fn checked_add(self, other: Self) -> Result<Self, ComplexError> {
+        let g = gcd(self.den, other.den) as i128;
+        let num = self.num.checked_mul(other.den / g)
+            .and_then(|a| other.num.checked_mul(self.den / g).and_then(|b| a.checked_add(b)))
+            .ok_or(ComplexError::Overflow)?;
+        let den = (self.den / g).checked_mul(other.den).ok_or(ComplexError::Overflow)?;
+
+        Rational::new(num, den)
+    }
+
+    fn checked_sub(self, other: Self) -> Result<Self, ComplexError> {
+        self.checked_add(Rational { num: other.num.checked_neg().ok_or(ComplexError::Overflow)?, den: other.den })
+    }
+
+    fn checked_mul(self, other: Self) -> Result<Self, ComplexError> {
+        // cancel first to keep the products small
+        let g1 = gcd(self.num, other.den) as i128;
+        let g2 = gcd(other.num, self.den) as i128;
+        let num = (self.num / g1).checked_mul(other.num / g2).ok_or(ComplexError::Overflow)?;
+        let den = (self.den / g2).checked_mul(other.den / g1).ok_or(ComplexError::Overflow)?;
+
+        Rational::new(num, den)
+    }
+
+    /// Parse a fraction such as `-2/7`, or a decimal such as `0.25` or
+    /// `1.5e-3`, which is converted exactly.
+    fn from_decimal_str(s: &str) -> Option<Self> {
+        if let Some((num, den)) = s.split_once('/') {
+            return Rational::new(num.parse().ok()?, den.parse().ok()?).ok();
+        }
+
+        let (mantissa, exponent) = match s.split_once(['e', 'E']) {
+            Some((m, e)) => (m, e.parse::<i32>().ok()?),
+            None => (s, 0),
+        };
+        let (whole, fraction) = mantissa.split_once('.').unwrap_or((mantissa, ""));
+        let digits: i128 = format!("{whole}{fraction}").parse().ok()?;
+        let exponent = exponent - fraction.len() as i32;
+
+        let scale = 10i128.checked_pow(exponent.unsigned_abs())?;
+        if exponent >= 0 {
+            Rational::new(digits.checked_mul(scale)?, 1).ok()
+        } else {
+            Rational::new(digits, scale).ok()
+        }
+    }
+
+    fn to_std_string(&self, _magnitude: usize, _precision: usize) -> String {
+        self.to_string()
+    }
+
+    fn to_literal_string(&self) -> String {
+        self.to_string()
+    }
+}
+
+impl Ord for Rational {
+    // Compares whole parts and then, if they're the same, the reciprocals of
+    // the fractional parts, which avoids the overflow of cross-multiplying.
+    fn cmp(&self, other: &Self) -> Ordering {
+        let (q1, r1) = (self.num.div_euclid(self.den), self.num.rem_euclid(self.den));
+        let (q2, r2) = (other.num.div_euclid(other.den), other.num.rem_euclid(other.den));
+
+        match (q1.cmp(&q2), r1, r2) {
+            (Ordering::Equal, 0, 0) => Ordering::Equal,
+            (Ordering::Equal, 0, _) => Ordering::Less,
+            (Ordering::Equal, _, 0) => Ordering::Greater,
+            (Ordering::Equal, _, _) => {
+                Rational { num: other.den, den: r2 }.cmp(&Rational { num: self.den, den: r1 })
+            }
+            (ordering, _, _) => ordering,
+        }
+    }
+}
+
+impl PartialOrd for Rational {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl fmt::Display for Rational {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.den == 1 {
+            write!(f, "{}", self.num)
+        } else {
+            write!(f, "{}/{}", self.num, self.den)
+        }
+    }
+}
+
+impl Add for Rational {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        self.checked_add(other).expect("rational addition overflowed")
+    }
+}
+
+impl Sub for Rational {
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self {
+        self.checked_sub(other).expect("rational subtraction overflowed")
+    }
+}
+
+impl Mul for Rational {
+    type Output = Self;
+
+    fn mul(self, other: Self) -> Self {
+        self.checked_mul(other).expect("rational multiplication overflowed")
+    }
+}
+
+impl Div for Rational {
+    type Output = Self;
+
+    fn div(self, other: Self) -> Self {
+        self.checked_div(other).expect("rational division by zero or overflowed")
+    }
+}
+
+impl Neg for Rational {
+    type Output = Self;
+
+    // the numerator is never i128::MIN, so this can't overflow
+    fn neg(self) -> Self {
+        Rational { num: -self.num, den: self.den }
+    }
+}
+
+impl Complex<Rational> {
+    /// Exact division, returning an error for division by zero or overflow.
+    pub fn checked_div(&self, other: Self) -> Result<Self, ComplexError> {
+        let d = other.checked_norm()?;
+        if d == Rational::ZERO {
+            return Err(ComplexError::DivisionByZero);
+        }
+
+        let n = self.checked_mul(other.conj())?;
+
+        Ok(Complex::new(n.re.checked_div(d)?, n.im.checked_div(d)?))
+    }
+
+    /// Raise to a whole number power, which may be negative.
+    pub fn checked_powi(&self, n: i32) -> Result<Self, ComplexError> {
+        let p = self.checked_pow(n.unsigned_abs())?;
+
+        if n < 0 {
+            Self::ONE.checked_div(p)
+        } else {
+            Ok(p)
+        }
+    }
+
+    /// The nearest `Complex64`.
+    pub fn to_complex64(&self) -> Complex64 {
+        Complex::new(self.re.to_f64(), self.im.to_f64())
+    }
+}
+
+/// Exact, see `TryFrom<f64> for Rational`.
+impl TryFrom<Complex64> for RationalComplex {
+    type Error = ComplexError;
+
+    fn try_from(c: Complex64) -> Result<Self, ComplexError> {
+        Ok(Complex::new(Rational::try_from(c.re)?, Rational::try_from(c.im)?))
+    }
+}
+
+impl From<GaussianInt> for RationalComplex {
+    fn from(z: GaussianInt) -> Self {
+        Complex::new(z.re.into(), z.im.into())
+    }
+}
+
+fn gcd(a: i128, b: i128) -> u128 {
+    let (mut a, mut b) = (a.unsigned_abs(), b.unsigned_abs());
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    a
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse_complex::from_rectangular_str;
+    use rstest::rstest;
+
+    fn r(num: i128, den: i128) -> Rational {
+        Rational::new(num, den).unwrap()
+    }
+
+    #[rstest(
+        num, den, expected,
+        case::lowest_terms(6, 8, "3/4"),
+        case::negative_denominator(1, -3, "-1/3"),
+        case::integer(-10, 5, "-2"),
+        case::zero(0, 7, "0"),
+        case::min_reduced(i128::MIN, 2, "-85070591730234615865843651857942052864")
+    )]
+    fn new_works(num: i128, den: i128, expected: &str) {
+        assert_eq!(expected, r(num, den).to_string());
+    }
+
+    #[rstest(
+        a, b, sum, product, quotient,
+        case::thirds(r(1, 3), r(2, 3), r(1, 1), r(2, 9), r(1, 2)),
+        case::mixed(r(-1, 2), r(3, 4), r(1, 4), r(-3, 8), r(-2, 3))
+    )]
+    fn arithmetic(a: Rational, b: Rational, sum: Rational, product: Rational, quotient: Rational) {
+        assert_eq!(Ok(sum), a.checked_add(b));
+        assert_eq!(Ok(a), sum.checked_sub(b));
+        assert_eq!(Ok(product), a.checked_mul(b));
+        assert_eq!(Ok(quotient), a.checked_div(b));
+    }
+
+    #[test]
+    fn overflow() {
+        let big = Rational::from_integer(i128::MAX);
+        assert_eq!(Err(ComplexError::Overflow), big.checked_add(Rational::ONE));
+        assert_eq!(Err(ComplexError::Overflow), big.checked_mul(Rational::from_integer(2)));
+        assert_eq!(Err(ComplexError::DivisionByZero), Rational::ONE.checked_div(Rational::ZERO));
+        assert_eq!(Err(ComplexError::Overflow), Rational::new(i128::MIN, 3));
+        assert_eq!(Err(ComplexError::Overflow), Rational::from_integer(-i128::MAX).checked_sub(Rational::ONE));
+        assert_eq!(r(i128::MAX, 3), -r(-i128::MAX, 3));
+    }
+
+    #[rstest(
+        a, b, expected,
+        case::less(r(1, 3), r(1, 2), Ordering::Less),
+        case::negative(r(-1, 3), r(-1, 2), Ordering::Greater),
+        case::equal(r(2, 4), r(1, 2), Ordering::Equal),
+        case::close(r(i128::MAX - 1, i128::MAX), r(i128::MAX - 2, i128::MAX - 1), Ordering::Greater)
+    )]
+    fn ordering(a: Rational, b: Rational, expected: Ordering) {
+        assert_eq!(expected, a.cmp(&b));
+    }
+
+    #[rstest(
+        s, expected,
+        case::fraction("-2/7", Some(r(-2, 7))),
+        case::decimal("0.25", Some(r(1, 4))),
+        case::exponent("1.5e-3", Some(r(3, 2000))),
+        case::big_exponent("2e3", Some(r(2000, 1))),
+        case::zero_denominator("1/0", None),
+        case::min("-170141183460469231731687303715884105728", None),
+        case::not_a_number("x", None)
+    )]
+    fn from_decimal_str_works(s: &str, expected: Option<Rational>) {
+        assert_eq!(expected, Rational::from_decimal_str(s));
+    }
+
+    #[rstest(
+        f, expected,
+        case::half(0.5, Ok(r(1, 2))),
+        case::negative(-3.75, Ok(r(-15, 4))),
+        case::tenth(0.1, Ok(r(3602879701896397, 36028797018963968))),
+        case::too_small(1e-300, Err(ComplexError::Overflow)),
+        case::too_big(1e300, Err(ComplexError::Overflow)),
+        case::nan(f64::NAN, Err(ComplexError::NaNProduced))
+    )]
+    fn from_f64(f: f64, expected: Result<Rational, ComplexError>) {
+        assert_eq!(expected, Rational::try_from(f));
+    }
+
+    #[test]
+    fn complex_parse_and_display() {
+        let z: RationalComplex = from_rectangular_str("{1/3 + 2/7i}").unwrap();
+        assert_eq!(Complex::new(r(1, 3), r(2, 7)), z);
+        assert_eq!("{1/3 + 2/7i}", z.to_string());
+    }
+
+    #[test]
+    fn complex_div() {
+        let a = Complex::new(r(1, 1), r(1, 1));
+        let b = Complex::new(r(1, 2), r(-1, 3));
+        let q = a.checked_div(b).unwrap();
+        assert_eq!(Complex::new(r(6, 13), r(30, 13)), q);
+        assert_eq!(Ok(a), q.checked_mul(b));
+        assert_eq!(Err(ComplexError::DivisionByZero), a.checked_div(RationalComplex::ZERO));
+    }
+
+    #[test]
+    fn complex_powi() {
+        let z = Complex::new(r(1, 2), r(1, 2));
+        assert_eq!(Ok(Complex::new(r(0, 1), r(1, 2))), z.checked_powi(2));
+        assert_eq!(Ok(Complex::new(r(0, 1), r(-2, 1))), z.checked_powi(-2));
+    }
+
+    #[test]
+    fn conversions() {
+        let z = Complex::new(0.5, -0.25);
+        let exact = RationalComplex::try_from(z).unwrap();
+        assert_eq!(Complex::new(r(1, 2), r(-1, 4)), exact);
+        assert_eq!(z, exact.to_complex64());
+        assert_eq!(Complex::new(r(3, 1), r(-4, 1)), RationalComplex::from(GaussianInt::new(3, -4)));
+    }
+}
@@ -0,0 +1,48 @@
+/// A range of bytes in an input line, such as the text of one token.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Span {
+    pub start: usize,
+    pub end: usize,
+}
+
+impl Span {
+    pub fn new(start: usize, end: usize) -> Self {
+        Span { start, end }
+    }
+
+    /// The span moved along by `offset` bytes.
+    pub fn shifted(&self, offset: usize) -> Self {
+        Span::new(self.start + offset, self.end + offset)
+    }
+
+    /// The column in `input`, counting characters from 1, that the span
+    /// starts at.
+    pub fn column(&self, input: &str) -> usize {
+        input[..self.start].chars().count() + 1
+    }
+
+    /// A line to print beneath `input` that marks the span as `^~~~`, with a
+    /// lone `^` for an empty span such as the end of the input.
+    pub fn underline(&self, input: &str) -> String {
+        let width = input[self.start..self.end].chars().count();
+
+        format!("{}^{}", " ".repeat(self.column(input) - 1), "~".repeat(width.saturating_sub(1)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    #[rstest(
+        input, span, expected,
+        case::first("{1} + * {2}", Span::new(6, 7), "      ^"),
+        case::token("{1} + {2 ++ i}", Span::new(6, 14), "      ^~~~~~~~"),
+        case::end("{1} +", Span::new(5, 5), "     ^"),
+        case::after_wide_characters("10∠90° ) 1", Span::new(10, 11), "       ^")
+    )]
+    fn underline(input: &str, span: Span, expected: &str) {
+        assert_eq!(expected, span.underline(input));
+    }
+}
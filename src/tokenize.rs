@@ -1,13 +1,18 @@
 use std::fmt;
 
 use crate::complex::Complex;
+use crate::domain::Domain;
+use crate::error::{ComplexError, LocatedError};
+use crate::span::Span;
 
-use crate::parse_complex::from_str;
-
+/// A token of a shell expression, with numbers from the domain `N` that the
+/// expression is being evaluated in.
 #[derive(Debug, Clone)]
-pub enum Token {
+pub enum Token<N = Complex> {
     Plus,
     Minus,
+    UnaryPlus,
+    UnaryMinus,
     Divide,
     Multiply,
     Power,
@@ -19,34 +24,33 @@ pub enum Token {
     CloseVector,
     Comma,
     Dot,
-    ComplexNumber(Complex),
-    Function(String, String),
+    ComplexNumber(N),
+    ComplexVector(Vec<N>),
+    /// The name of a variable or function.
+    Identifier(String),
+    Equals,
 }
 
-impl Token {
-    pub fn precedence(&self) -> u8 {
-        match self {
-            Token::Minus => 1,
-            Token::Plus => 2,
-            Token::Multiply | Token::Divide => 3,
-            Token::Power => 4,
-            _ => 0,
-        }
-    }
-
-    pub fn is_left_associative(&self) -> bool {
-        matches!(self, Token::Power)
-    }
-
+impl<N> Token<N> {
     pub fn to_symbol(&self) -> String {
         match self {
             Token::Plus => "+".to_owned(),
             Token::Minus => "-".to_owned(),
+            Token::UnaryPlus => "u+".to_owned(),
+            Token::UnaryMinus => "u-".to_owned(),
             Token::Multiply => "*".to_owned(),
             Token::Divide => "/".to_owned(),
             Token::Dot => ".".to_owned(),
             Token::Power => "^".to_owned(),
-            Token::Function(f, args) => format!("{}{}", f.to_owned(), args.to_owned()),
+            Token::Conjugate => "~".to_owned(),
+            Token::LeftParen => "(".to_owned(),
+            Token::RightParen => ")".to_owned(),
+            Token::Transpose => "`".to_owned(),
+            Token::OpenVector => "[".to_owned(),
+            Token::CloseVector => "]".to_owned(),
+            Token::Comma => ",".to_owned(),
+            Token::Equals => "=".to_owned(),
+            Token::Identifier(name) => name.to_owned(),
             _ => "#".to_owned(),
         }
     }
@@ -74,7 +78,7 @@ impl Token {
     }*/
 //}
 
-impl fmt::Display for Token {
+impl<N: Domain> fmt::Display for Token<N> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
             Token::ComplexNumber(c) => {
@@ -82,8 +86,14 @@ impl fmt::Display for Token {
                 let precision = f.precision().unwrap_or(6);
                 write!(f, "{}", c.to_std_string(magnitude, precision))
             }
-            Token::Function(ftn, args) => {
-                write!(f, "{}({})", ftn, args)
+            Token::ComplexVector(v) => {
+                let magnitude = f.width().unwrap_or(12);
+                let precision = f.precision().unwrap_or(6);
+                let parts: Vec<String> = v.iter().map(|c| c.to_std_string(magnitude, precision)).collect();
+                write!(f, "[{}]", parts.join(", "))
+            }
+            Token::Identifier(name) => {
+                write!(f, "{}", name)
             }
             _ => {
                 write!(f, "{}", self.to_symbol())
@@ -92,212 +102,193 @@ impl fmt::Display for Token {
     }
 }
 
-pub fn tokenize(input: &str) -> Result<Vec<Token>, String> {
+pub fn tokenize(input: &str) -> Result<Vec<Token>, LocatedError> {
+    tokenize_as(input)
+}
+
+/// Tokenize, parsing the numbers as members of the domain `N`.
+pub fn tokenize_as<N: Domain>(input: &str) -> Result<Vec<Token<N>>, LocatedError> {
+    Ok(tokenize_spanned(input)?.into_iter().map(|(_, token)| token).collect())
+}
+
+/// `tokenize_as`, with each token paired with the span of input it was read
+/// from. An error is located at the text that could not be read.
+pub fn tokenize_spanned<N: Domain>(input: &str) -> Result<Vec<(Span, Token<N>)>, LocatedError> {
     let mut tokens = Vec::new();
     let mut buffer = String::new();
-    let mut pos = 0;
+    let mut start = 0;
     let mut capturing_complex = false;
-    let mut capturing_function = false;
-    let mut function = String::new();
-    let mut capturing_function_args = false;
-    let mut function_bracket_nesting: u8 = 0;
+    let mut capturing_number = false;
+    let mut capturing_identifier = false;
 
-    for c in input.chars() {
-        pos += 1;
+    for (i, c) in input.char_indices() {
+        let here = Span::new(i, i + c.len_utf8());
 
-        if capturing_complex {
-            buffer.push(c);
-            if c == '}' {
-                match from_str(buffer.as_str()) {
-                    Ok(complex) => {
-                        tokens.push(Token::ComplexNumber(complex));
-                        buffer.clear();
-                    }
-                    Err(s) => {
-                        println!("Error at char {}: {}", pos, s);
-                        break;
-                    }
-                }
-                buffer.clear();
-                capturing_complex = false;
-            }
-        } else if capturing_function {
-            if c == '(' {
-                function = buffer.clone();
-                buffer.clear();
-                capturing_function = false;
-                capturing_function_args = true;
-                function_bracket_nesting = 1;
-            } else {
+        if capturing_number {
+            if continues_number(&buffer, c) {
                 buffer.push(c);
+                continue;
             }
-        } else if capturing_function_args {
-            if c == ')' {
-                function_bracket_nesting -= 1;
-            } else if c == '(' {
-                function_bracket_nesting += 1;
-            }
 
-            if function_bracket_nesting > 0 {
+            tokens.push((Span::new(start, i), Token::ComplexNumber(literal(&buffer, start)?)));
+            buffer.clear();
+            capturing_number = false;
+        }
+
+        if capturing_identifier {
+            if continues_identifier(c) {
                 buffer.push(c);
-            } else {
-                tokens.push(Token::Function(function.clone(), buffer.clone()));
-                capturing_function_args = false;
+                continue;
+            }
+
+            tokens.push((Span::new(start, i), identifier(&buffer, start)?));
+            buffer.clear();
+            capturing_identifier = false;
+        }
+
+        if capturing_complex {
+            buffer.push(c);
+            if c == '}' {
+                tokens.push((Span::new(start, here.end), Token::ComplexNumber(literal(&buffer, start)?)));
                 buffer.clear();
+                capturing_complex = false;
             }
         } else {
             match c {
                 '{' | '@' => {
+                    start = i;
                     capturing_complex = true;
                     buffer.clear();
                     buffer.push(c);
                 }
+                '+' if follows_operand(&tokens) => {
+                    tokens.push((here, Token::Plus));
+                }
                 '+' => {
-                    tokens.push(Token::Plus);
+                    tokens.push((here, Token::UnaryPlus));
+                }
+                '-' if follows_operand(&tokens) => {
+                    tokens.push((here, Token::Minus));
                 }
                 '-' => {
-                    tokens.push(Token::Minus);
+                    tokens.push((here, Token::UnaryMinus));
                 }
                 '*' => {
-                    tokens.push(Token::Multiply);
+                    tokens.push((here, Token::Multiply));
                 }
                 '/' => {
-                    tokens.push(Token::Divide);
+                    tokens.push((here, Token::Divide));
                 }
                 '^' => {
-                    tokens.push(Token::Power);
+                    tokens.push((here, Token::Power));
                 }
                 ')' => {
-                    tokens.push(Token::RightParen);
+                    tokens.push((here, Token::RightParen));
                 }
                 '(' => {
-                    tokens.push(Token::LeftParen);
+                    tokens.push((here, Token::LeftParen));
                 }
                 '~' => {
-                    tokens.push(Token::Conjugate);
+                    tokens.push((here, Token::Conjugate));
                 }
                 '`' => {
-                    tokens.push(Token::Transpose);
+                    tokens.push((here, Token::Transpose));
                 }
                 '[' => {
-                    tokens.push(Token::OpenVector);
+                    tokens.push((here, Token::OpenVector));
                 }
                 ']' => {
-                    tokens.push(Token::CloseVector);
+                    tokens.push((here, Token::CloseVector));
                 }
                 ',' => {
-                    tokens.push(Token::Comma);
+                    tokens.push((here, Token::Comma));
+                }
+                '=' => {
+                    tokens.push((here, Token::Equals));
+                }
+                '0'..='9' | '.' => {
+                    start = i;
+                    capturing_number = true;
+                    buffer.clear();
+                    buffer.push(c);
                 }
+                _ if c.is_alphabetic() || c == '_' => {
+                    start = i;
+                    capturing_identifier = true;
+                    buffer.clear();
+                    buffer.push(c);
+                }
+                _ if c.is_whitespace() => {}
                 _ => {
-                    if !c.is_whitespace() {
-                        capturing_function = true;
-                        buffer.clear();
-                        buffer.push(c);
-                    }
+                    let column = here.column(input);
+                    return Err(ComplexError::SyntaxError(format!("unexpected character '{c}' at column {column}")).at(here));
                 }
             }
         }
     }
 
-    if !buffer.is_empty() {
-        let c = from_str(buffer.as_str());
-        match c {
-            Ok(c) => {
-                tokens.push(Token::ComplexNumber(c));
-            }
-            Err(_) => {
-                println!("Error at position {}", pos);
-            }
-        }
+    if capturing_identifier {
+        tokens.push((Span::new(start, input.len()), identifier(&buffer, start)?));
+    } else if !buffer.is_empty() {
+        // a number, or a bracket that was never closed
+        tokens.push((Span::new(start, input.len()), Token::ComplexNumber(literal(&buffer, start)?)));
     }
 
     Ok(tokens)
 }
 
-pub fn shunting_yard(input: Vec<Token>) -> Vec<Token> {
-    let mut operators: Vec<Token> = Vec::new();
-    let mut output: Vec<Token> = Vec::new();
+// A name, except that i and j on their own are the imaginary unit.
+fn identifier<N: Domain>(name: &str, start: usize) -> Result<Token<N>, LocatedError> {
+    match name {
+        "i" | "I" | "j" | "J" => Ok(Token::ComplexNumber(literal(name, start)?)),
+        _ => Ok(Token::Identifier(name.to_owned())),
+    }
+}
 
-    // TODO: Implement the rest of the algorithm from
-    // https://en.wikipedia.org/wiki/Shunting_yard_algorithm
-    for o1 in input {
-        match o1 {
-            Token::ComplexNumber(_) => {
-                output.push(o1);
-            }
-            Token::Function(_, _) => {
-                operators.push(o1);
-            }
-            Token::LeftParen => {
-                operators.push(o1);
+// Parse a literal that starts `start` bytes into the input, locating an error
+// at the character that the parse stopped at, or else at the whole literal.
+fn literal<N: Domain>(text: &str, start: usize) -> Result<N, LocatedError> {
+    N::parse_literal(text).map_err(|e| {
+        let span = match &e {
+            ComplexError::ParseError { input, position, .. } => {
+                let width = input[*position..].chars().next().map_or(0, char::len_utf8);
+                Span::new(start + position, start + position + width)
             }
-            Token::Comma => loop {
-                let o2_opt = operators.last();
-                match o2_opt {
-                    Some(o2) => match o2 {
-                        Token::LeftParen => {
-                            break;
-                        }
-                        _ => {
-                            output.push(operators.pop().unwrap());
-                        }
-                    },
-                    None => {
-                        break;
-                    }
-                }
-            },
-            Token::RightParen => loop {
-                let o2_opt = operators.last();
-                match o2_opt {
-                    Some(o2) => {
-                        if !matches!(o2, Token::LeftParen) {
-                            output.push(operators.pop().unwrap());
-                        } else {
-                            operators.pop();
-                        }
-                    }
-                    None => {
-                        break;
-                    }
-                }
-            },
-            _ => {
-                loop {
-                    let o2_opt = operators.last();
-                    match o2_opt {
-                        Some(o2) => {
-                            if !matches!(o2, Token::LeftParen)
-                                && (o2.precedence() > o1.precedence()
-                                    || (o1.is_left_associative()
-                                        && o2.precedence() == o1.precedence()))
-                            {
-                                output.push(operators.pop().unwrap());
-                            } else {
-                                break;
-                            }
-                        }
-                        None => {
-                            break;
-                        }
-                    }
-                }
+            _ => Span::new(start, start + text.len()),
+        };
 
-                operators.push(o1);
-            }
-        }
-    }
+        e.at(span)
+    })
+}
 
-    while let Some(o) = operators.pop() {
-        output.push(o);
+// Whether the next token comes after an operand, in which case a sign is the
+// binary operator rather than the unary one.
+fn follows_operand<N>(tokens: &[(Span, Token<N>)]) -> bool {
+    match tokens.split_last() {
+        Some(((_, Token::ComplexNumber(_) | Token::ComplexVector(_) | Token::Identifier(_) | Token::RightParen | Token::CloseVector), _)) => true,
+        // a `~` after an operand conjugates it, and so ends an operand too
+        Some(((_, Token::Conjugate), rest)) => follows_operand(rest),
+        _ => false,
     }
+}
 
-    output
+fn continues_identifier(c: char) -> bool {
+    c.is_alphanumeric() || c == '_'
+}
+
+// Whether c carries on a number written without brackets, such as the phasor
+// `10∠-45°`. A sign only carries on an exponent or an angle.
+fn continues_number(buffer: &str, c: char) -> bool {
+    match c {
+        '+' | '-' => buffer.ends_with(['e', 'E', '∠']),
+        _ => c.is_alphanumeric() || matches!(c, '.' | '∠' | '°'),
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::parse_complex::LiteralFormat;
     use rstest::rstest;
 
     #[test]
@@ -310,24 +301,30 @@ mod tests {
     #[rstest(
         input,
         expected,
-        case::simple_plus("{1} + {2}", "{1} {2} +"),
-        case::plus_then_times("{1} + {2} * {3}", "{1} {2} {3} * +"),
-        case::times_then_plus("{1} * {2} + {3}", "{1} {2} * {3} +"),
-        case::plus_then_times_then_minux("{1} + {2} * {3} - {4}", "{1} {2} {3} * + {4} -"),
-        case::unary_function("z({1})", "z({1})"),
-        case::binary_function("pow({1}, {10})", "pow({1}, {10})"),
-        case::multi_function("sum({1}, {2}, {3}, {4})", "sum({1}, {2}, {3}, {4})")
+        case::simple_plus("{1} + {2}", "{1} + {2}"),
+        case::unary_function("z({1})", "z ( {1} )"),
+        case::binary_function("pow({1}, {10})", "pow ( {1} , {10} )"),
+        case::function_operand("{1} + sin({2}) * {3}", "{1} + sin ( {2} ) * {3}"),
+        case::conjugate("~{1} * {2}", "~ {1} * {2}"),
+        case::negate_power("-{2}^{2}", "u- {2} ^ {2}"),
+        case::power_of_negative("{2}^-{2}", "{2} ^ u- {2}"),
+        case::negate_product("-{2} * +{3}", "u- {2} * u+ {3}"),
+        case::binary_after_bracket("({1}) - {2}", "( {1} ) - {2}"),
+        case::negate_function("{1} - -sin({2})", "{1} - u- sin ( {2} )"),
+        case::variables("z_1 - w2", "z_1 - w2"),
+        case::assignment("z = i - j", "z = {i} - {i}"),
+        case::phasors("10∠90° * 2∠-90deg", "{10i} * {-2i}"),
+        case::bare_number("2.5 + {i}", "{2.5} + {i}"),
+        case::exponent("1e-2*{2}", "{0.01} * {2}"),
+        case::bare_complex("2 + 3i", "{2} + {3i}"),
+        case::bare_j("2j-1", "{2i} - {1}"),
+        case::bare_exponent_imaginary("3.5e-2 * 4E+1i", "{0.035} * {40i}"),
+        case::leading_point(".5^2", "{0.5} ^ {2}"),
+        case::mixed_forms("1 + {2, 1} - @{1, 0}", "{1} + {2 + i} - {1}")
     )]
-    fn shunting_works(input: &str, expected: &str) {
-        let tokens = tokenize(input).unwrap();
-
-        println!("TOKENS = {:?}", tokens);
-
-        let result = shunting_yard(tokens);
-
-        println!("SHUNTED = {:?}", result);
-
-        let result = result
+    fn tokenize_works(input: &str, expected: &str) {
+        let result = tokenize(input)
+            .unwrap()
             .iter()
             .map(|r| r.to_string())
             .collect::<Vec<_>>()
@@ -335,4 +332,46 @@ mod tests {
 
         assert_eq!(expected, result);
     }
+
+    #[rstest(
+        input, expected,
+        case::operators("{1} + -{2}", vec![(0, 3), (4, 5), (6, 7), (7, 10)]),
+        case::bare_numbers("12 * 3i", vec![(0, 2), (3, 4), (5, 7)]),
+        case::function("{1}*sqrt ({4})", vec![(0, 3), (3, 4), (4, 8), (9, 10), (10, 13), (13, 14)]),
+        case::assignment("zα = 1", vec![(0, 3), (4, 5), (6, 7)]),
+        case::phasor("(2∠90°)", vec![(0, 1), (1, 9), (9, 10)])
+    )]
+    fn spans(input: &str, expected: Vec<(usize, usize)>) {
+        let spans: Vec<(usize, usize)> = tokenize_spanned::<Complex>(input)
+            .unwrap()
+            .into_iter()
+            .map(|(span, _)| (span.start, span.end))
+            .collect();
+
+        assert_eq!(expected, spans);
+    }
+
+    #[rstest(
+        input, literal, position, span,
+        case::letters("2x + 1", "2x", 1, Span::new(1, 2)),
+        case::two_points("1 - 1.2.3", "1.2.3", 3, Span::new(7, 8)),
+        case::dangling_exponent("4e * 2", "4e", 2, Span::new(2, 2))
+    )]
+    fn bare_literal_error(input: &str, literal: &str, position: usize, span: Span) {
+        let expected = ComplexError::ParseError { input: literal.to_owned(), format: LiteralFormat::Standard, position };
+        assert_eq!(expected.at(span), tokenize(input).unwrap_err());
+    }
+
+    #[rstest(
+        input, expected, span,
+        case::unclosed_bracket("{1} + {2 + i", "Cannot parse '{2 + i' as bracketed standard form {a + bi}: unexpected end at position 6", Span::new(12, 12)),
+        case::bad_bracket("{1 + 2x} * 2", "Cannot parse '{1 + 2x}' as bracketed standard form {a + bi}: unexpected 'x' at position 6", Span::new(6, 7)),
+        case::stray_bracket("{2} + {i}}", "Syntax error: unexpected character '}' at column 10", Span::new(9, 10)),
+        case::unknown_character("2 $ 3", "Syntax error: unexpected character '$' at column 3", Span::new(2, 3))
+    )]
+    fn located_error(input: &str, expected: &str, span: Span) {
+        let e = tokenize(input).unwrap_err();
+        assert_eq!(expected, e.to_string());
+        assert_eq!(span, e.span);
+    }
 }
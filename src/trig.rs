@@ -0,0 +1,373 @@
+use crate::complex::{finite, Complex};
+use crate::error::ComplexError;
+use crate::num::Float;
+
+// Trigonometric and hyperbolic functions of a complex number, along with their
+// inverses.
+//
+// The inverse functions have their branch cuts where C99 Annex G puts them:
+//
+// - asin, acos: on the real axis outside [-1, 1]
+// - atan: on the imaginary axis outside [-i, i]
+// - asinh: on the imaginary axis outside [-i, i]
+// - acosh: on the real axis left of 1
+// - atanh: on the real axis outside [-1, 1]
+//
+// On a cut the sign of the zero part picks the side, so `asin(2 + 0i)` and
+// `asin(2 - 0i)` are conjugates. The inverse sine, cosine and hyperbolic cosine
+// use Kahan's formulas ("Branch Cuts for Complex Elementary Functions", 1987)
+// which respect signed zeros, and the rest are derived from them.
+impl<T: Float> Complex<T> {
+    /// `sin(a + bi) = sin a cosh b + i cos a sinh b`
+    pub fn sin(&self) -> Result<Self, ComplexError> {
+        finite(self.ieee_sin())
+    }
+
+    /// `cos(a + bi) = cos a cosh b - i sin a sinh b`
+    pub fn cos(&self) -> Result<Self, ComplexError> {
+        finite(self.ieee_cos())
+    }
+
+    /// `tan(z) = -i tanh(iz)`
+    pub fn tan(&self) -> Result<Self, ComplexError> {
+        Ok(mul_minus_i(mul_i(*self).tanh()?))
+    }
+
+    /// `sec(z) = 1 / cos(z)`
+    pub fn sec(&self) -> Result<Self, ComplexError> {
+        Self::ONE.checked_div(self.cos()?)
+    }
+
+    /// `csc(z) = 1 / sin(z)`
+    pub fn csc(&self) -> Result<Self, ComplexError> {
+        Self::ONE.checked_div(self.sin()?)
+    }
+
+    /// `cot(z) = 1 / tan(z)`
+    pub fn cot(&self) -> Result<Self, ComplexError> {
+        Self::ONE.checked_div(self.tan()?)
+    }
+
+    /// `sinh(a + bi) = sinh a cos b + i cosh a sin b`
+    pub fn sinh(&self) -> Result<Self, ComplexError> {
+        finite(self.ieee_sinh())
+    }
+
+    /// `cosh(a + bi) = cosh a cos b + i sinh a sin b`
+    pub fn cosh(&self) -> Result<Self, ComplexError> {
+        finite(self.ieee_cosh())
+    }
+
+    /// The hyperbolic tangent. This uses Kahan's formulation, which stays
+    /// accurate where `sinh(z) / cosh(z)` would overflow.
+    pub fn tanh(&self) -> Result<Self, ComplexError> {
+        finite(self.ieee_tanh())
+    }
+
+    /// The principal inverse sine, with real part in `[-π/2, π/2]`.
+    pub fn asin(&self) -> Result<Self, ComplexError> {
+        finite(self.ieee_asin())
+    }
+
+    /// The principal inverse cosine, with real part in `[0, π]`.
+    pub fn acos(&self) -> Result<Self, ComplexError> {
+        finite(self.ieee_acos())
+    }
+
+    /// The principal inverse tangent, `atan(z) = -i atanh(iz)`, with real part
+    /// in `[-π/2, π/2]`. This is not defined at `±i`.
+    pub fn atan(&self) -> Result<Self, ComplexError> {
+        Ok(mul_minus_i(mul_i(*self).atanh()?))
+    }
+
+    /// The principal inverse hyperbolic sine, `asinh(z) = -i asin(iz)`, with
+    /// imaginary part in `[-π/2, π/2]`.
+    pub fn asinh(&self) -> Result<Self, ComplexError> {
+        Ok(mul_minus_i(mul_i(*self).asin()?))
+    }
+
+    /// The principal inverse hyperbolic cosine, with non-negative real part
+    /// and imaginary part in `[-π, π]`.
+    pub fn acosh(&self) -> Result<Self, ComplexError> {
+        finite(self.ieee_acosh())
+    }
+
+    /// The principal inverse hyperbolic tangent,
+    /// `atanh(z) = (ln(1 + z) - ln(1 - z)) / 2`, with imaginary part in
+    /// `[-π/2, π/2]`. This is not defined at `±1`. The parts are computed
+    /// apart, as Kahan does, so that small arguments don't cancel.
+    pub fn atanh(&self) -> Result<Self, ComplexError> {
+        if one_plus(*self) == Self::ZERO || one_minus(*self) == Self::ZERO {
+            return Err(ComplexError::DivisionByZero);
+        }
+
+        finite(self.ieee_atanh())
+    }
+}
+
+// The unchecked forms of the functions above, for ieee mode, where infinities
+// and NaNs carry through and poles are infinite.
+impl<T: Float> Complex<T> {
+    pub fn ieee_sin(&self) -> Self {
+        Complex::new(self.re.sin() * self.im.cosh(), self.re.cos() * self.im.sinh())
+    }
+
+    pub fn ieee_cos(&self) -> Self {
+        Complex::new(self.re.cos() * self.im.cosh(), -self.re.sin() * self.im.sinh())
+    }
+
+    pub fn ieee_tan(&self) -> Self {
+        mul_minus_i(mul_i(*self).ieee_tanh())
+    }
+
+    pub fn ieee_sec(&self) -> Self {
+        Self::ONE.ieee_div(self.ieee_cos())
+    }
+
+    pub fn ieee_csc(&self) -> Self {
+        Self::ONE.ieee_div(self.ieee_sin())
+    }
+
+    pub fn ieee_cot(&self) -> Self {
+        Self::ONE.ieee_div(self.ieee_tan())
+    }
+
+    pub fn ieee_sinh(&self) -> Self {
+        Complex::new(self.re.sinh() * self.im.cos(), self.re.cosh() * self.im.sin())
+    }
+
+    pub fn ieee_cosh(&self) -> Self {
+        Complex::new(self.re.cosh() * self.im.cos(), self.re.sinh() * self.im.sin())
+    }
+
+    pub fn ieee_tanh(&self) -> Self {
+        // tanh(a) = 1 - 2e^(-2a) + ..., which is 1 to within the type's
+        // precision beyond this
+        let cutoff = (T::from_f64(4.0) / T::epsilon()).ln() / T::from_f64(2.0);
+        if self.re.abs() > cutoff {
+            let e = (T::from_f64(-2.0) * self.re.abs()).exp();
+            return Complex::new(
+                T::ONE.copysign(self.re),
+                T::from_f64(4.0) * self.im.sin() * self.im.cos() * e,
+            );
+        }
+
+        let t = self.im.tan();
+        let beta = T::ONE + t * t;
+        let s = self.re.sinh();
+        let rho = (T::ONE + s * s).sqrt();
+        let denom = T::ONE + beta * s * s;
+
+        Complex::new(beta * rho * s / denom, t / denom)
+    }
+
+    pub fn ieee_asin(&self) -> Self {
+        let s1 = one_minus(*self).ieee_sqrt();
+        let s2 = one_plus(*self).ieee_sqrt();
+
+        Complex::new(
+            self.re.atan2((s1 * s2).re),
+            (conj(s1) * s2).im.asinh(),
+        )
+    }
+
+    pub fn ieee_acos(&self) -> Self {
+        let s1 = one_minus(*self).ieee_sqrt();
+        let s2 = one_plus(*self).ieee_sqrt();
+
+        Complex::new(
+            two::<T>() * s1.re.atan2(s2.re),
+            (conj(s2) * s1).im.asinh(),
+        )
+    }
+
+    pub fn ieee_atan(&self) -> Self {
+        mul_minus_i(mul_i(*self).ieee_atanh())
+    }
+
+    pub fn ieee_asinh(&self) -> Self {
+        mul_minus_i(mul_i(*self).ieee_asin())
+    }
+
+    pub fn ieee_acosh(&self) -> Self {
+        let s1 = Complex::new(self.re - T::ONE, self.im).ieee_sqrt();
+        let s2 = Complex::new(self.re + T::ONE, self.im).ieee_sqrt();
+
+        Complex::new(
+            (conj(s1) * s2).re.asinh(),
+            two::<T>() * s1.im.atan2(s2.re),
+        )
+    }
+
+    pub fn ieee_atanh(&self) -> Self {
+        let (x, y) = (self.re, self.im);
+        let one_minus_x = T::ONE - x;
+
+        // re = ln(|1 + z|² / |1 - z|²) / 4, with the ratio written as
+        // 1 + 4x / |1 - z|²
+        let re = ln_1p(T::from_f64(4.0) * x / (one_minus_x * one_minus_x + y * y)) / T::from_f64(4.0);
+        let im = (two::<T>() * y).atan2(one_minus_x * (T::ONE + x) - y * y) / two::<T>();
+
+        Complex::new(re, im)
+    }
+}
+
+// The helpers below build their results part by part rather than through the
+// arithmetic operators so that the sign of a zero part is kept, which matters
+// for the functions with branch cuts.
+
+fn mul_i<T: Float>(z: Complex<T>) -> Complex<T> {
+    Complex::new(-z.im, z.re)
+}
+
+fn mul_minus_i<T: Float>(z: Complex<T>) -> Complex<T> {
+    Complex::new(z.im, -z.re)
+}
+
+fn one_plus<T: Float>(z: Complex<T>) -> Complex<T> {
+    Complex::new(T::ONE + z.re, z.im)
+}
+
+fn one_minus<T: Float>(z: Complex<T>) -> Complex<T> {
+    Complex::new(T::ONE - z.re, -z.im)
+}
+
+fn conj<T: Float>(z: Complex<T>) -> Complex<T> {
+    Complex::new(z.re, -z.im)
+}
+
+fn two<T: Float>() -> T {
+    T::ONE + T::ONE
+}
+
+// ln(1 + u), accurate for small u. The rounding error in w = 1 + u cancels in
+// ln(w) u / (w - 1) (Goldberg, "What Every Computer Scientist Should Know
+// About Floating-Point Arithmetic", 1991).
+fn ln_1p<T: Float>(u: T) -> T {
+    let w = T::ONE + u;
+    if w == T::ONE {
+        u
+    } else if w.is_infinite() {
+        w.ln()
+    } else {
+        w.ln() * u / (w - T::ONE)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f64::consts::{FRAC_PI_2, FRAC_PI_4, PI};
+
+    use super::*;
+    use rstest::rstest;
+
+    // Assert that two complex numbers are close to each other, with
+    // both parts not differing by more than a specified amount.
+    macro_rules! assert_complex_close {
+        ($x:expr, $y:expr, $df:expr) => {
+            if (($x.re - $y.re).abs() > $df || ($x.im - $y.im).abs() > $df) {
+                panic!("difference between {} and {} too large", $x, $y);
+            }
+        };
+    }
+
+    #[rstest(
+        f, z, expected,
+        case::sin_zero(Complex::sin, Complex::ZERO, Complex::ZERO),
+        case::sin(Complex::sin, Complex::new(1.0, 1.0), Complex::new(1.298458, 0.634964)),
+        case::cos_zero(Complex::cos, Complex::ZERO, Complex::ONE),
+        case::cos(Complex::cos, Complex::new(1.0, 1.0), Complex::new(0.833730, -0.988898)),
+        case::tan(Complex::tan, Complex::new(1.0, 1.0), Complex::new(0.271753, 1.083923)),
+        case::tan_large_im(Complex::tan, Complex::new(1.0, 100.0), Complex::I),
+        case::sec(Complex::sec, Complex::new(1.0, 1.0), Complex::new(0.498337, 0.591084)),
+        case::csc(Complex::csc, Complex::new(1.0, 1.0), Complex::new(0.621518, -0.303931)),
+        case::cot(Complex::cot, Complex::new(1.0, 1.0), Complex::new(0.217622, -0.868014)),
+        case::sinh(Complex::sinh, Complex::new(1.0, 1.0), Complex::new(0.634964, 1.298458)),
+        case::cosh(Complex::cosh, Complex::new(1.0, 1.0), Complex::new(0.833730, 0.988898)),
+        case::tanh(Complex::tanh, Complex::new(1.0, 1.0), Complex::new(1.083923, 0.271753)),
+        case::tanh_large_re(Complex::tanh, Complex::new(-50.0, 1.0), Complex::new(-1.0, 0.0))
+    )]
+    fn forward(f: fn(&Complex) -> Result<Complex, ComplexError>, z: Complex, expected: Complex) {
+        let result = f(&z).unwrap();
+        assert_complex_close!(expected, result, 0.000001);
+    }
+
+    #[rstest(
+        f, z, expected,
+        case::asin(Complex::asin, Complex::new(1.0, 1.0), Complex::new(0.666239, 1.061275)),
+        case::asin_one(Complex::asin, Complex::ONE, Complex::new(FRAC_PI_2, 0.0)),
+        case::asin_above_cut(Complex::asin, Complex::new(2.0, 0.0), Complex::new(FRAC_PI_2, 1.316958)),
+        case::asin_below_cut(Complex::asin, Complex::new(2.0, -0.0), Complex::new(FRAC_PI_2, -1.316958)),
+        case::acos(Complex::acos, Complex::new(1.0, 1.0), Complex::new(0.904557, -1.061275)),
+        case::acos_minus_one(Complex::acos, Complex::new(-1.0, 0.0), Complex::new(PI, 0.0)),
+        case::acos_above_cut(Complex::acos, Complex::new(2.0, 0.0), Complex::new(0.0, -1.316958)),
+        case::acos_below_cut(Complex::acos, Complex::new(2.0, -0.0), Complex::new(0.0, 1.316958)),
+        case::atan(Complex::atan, Complex::new(1.0, 1.0), Complex::new(1.017222, 0.402359)),
+        case::atan_one(Complex::atan, Complex::ONE, Complex::new(FRAC_PI_4, 0.0)),
+        case::atan_right_of_cut(Complex::atan, Complex::new(0.0, 2.0), Complex::new(FRAC_PI_2, 0.549306)),
+        case::atan_left_of_cut(Complex::atan, Complex::new(-0.0, 2.0), Complex::new(-FRAC_PI_2, 0.549306)),
+        case::asinh(Complex::asinh, Complex::new(1.0, 1.0), Complex::new(1.061275, 0.666239)),
+        case::asinh_right_of_cut(Complex::asinh, Complex::new(0.0, 2.0), Complex::new(1.316958, FRAC_PI_2)),
+        case::asinh_left_of_cut(Complex::asinh, Complex::new(-0.0, 2.0), Complex::new(-1.316958, FRAC_PI_2)),
+        case::acosh(Complex::acosh, Complex::new(1.0, 1.0), Complex::new(1.061275, 0.904557)),
+        case::acosh_one(Complex::acosh, Complex::ONE, Complex::ZERO),
+        case::acosh_above_cut(Complex::acosh, Complex::new(-2.0, 0.0), Complex::new(1.316958, PI)),
+        case::acosh_below_cut(Complex::acosh, Complex::new(-2.0, -0.0), Complex::new(1.316958, -PI)),
+        case::atanh(Complex::atanh, Complex::new(1.0, 1.0), Complex::new(0.402359, 1.017222)),
+        case::atanh_above_cut(Complex::atanh, Complex::new(2.0, 0.0), Complex::new(0.549306, FRAC_PI_2)),
+        case::atanh_below_cut(Complex::atanh, Complex::new(2.0, -0.0), Complex::new(0.549306, -FRAC_PI_2))
+    )]
+    fn inverse(f: fn(&Complex) -> Result<Complex, ComplexError>, z: Complex, expected: Complex) {
+        let result = f(&z).unwrap();
+        assert_complex_close!(expected, result, 0.000001);
+    }
+
+    #[rstest(
+        f, inv, z,
+        case::sin(Complex::sin, Complex::asin, Complex::new(0.5, -0.7)),
+        case::cos(Complex::cos, Complex::acos, Complex::new(0.5, -0.7)),
+        case::tan(Complex::tan, Complex::atan, Complex::new(0.5, -0.7)),
+        case::sinh(Complex::sinh, Complex::asinh, Complex::new(0.5, -0.7)),
+        case::cosh(Complex::cosh, Complex::acosh, Complex::new(0.5, -0.7)),
+        case::tanh(Complex::tanh, Complex::atanh, Complex::new(0.5, -0.7))
+    )]
+    fn round_trip(
+        f: fn(&Complex) -> Result<Complex, ComplexError>,
+        inv: fn(&Complex) -> Result<Complex, ComplexError>,
+        z: Complex,
+    ) {
+        let result = inv(&f(&z).unwrap()).unwrap();
+        assert_complex_close!(z, result, 0.000000001);
+    }
+
+    #[rstest(
+        f, z, expected,
+        case::sin_overflow(Complex::sin, Complex::new(1.0, 1000.0), ComplexError::Overflow),
+        case::cosh_overflow(Complex::cosh, Complex::new(1000.0, 1.0), ComplexError::Overflow),
+        case::atanh_one(Complex::atanh, Complex::ONE, ComplexError::DivisionByZero),
+        case::atan_i(Complex::atan, Complex::I, ComplexError::DivisionByZero),
+        case::csc_zero(Complex::csc, Complex::ZERO, ComplexError::DivisionByZero)
+    )]
+    fn errors(f: fn(&Complex) -> Result<Complex, ComplexError>, z: Complex, expected: ComplexError) {
+        assert_eq!(Err(expected), f(&z));
+    }
+
+    #[rstest(
+        f, z, expected,
+        case::atanh(Complex::atanh, Complex::new(1e-20, 0.0), Complex::new(1e-20, 0.0)),
+        case::atanh_imaginary(Complex::atanh, Complex::new(0.0, -1e-20), Complex::new(0.0, -1e-20)),
+        case::atan(Complex::atan, Complex::new(1e-20, 1e-30), Complex::new(1e-20, 1e-30))
+    )]
+    fn small_arguments(f: fn(&Complex) -> Result<Complex, ComplexError>, z: Complex, expected: Complex) {
+        assert_eq!(Ok(expected), f(&z));
+    }
+
+    #[test]
+    fn f32_agrees_with_f64() {
+        let z = Complex::new(0.5_f32, -0.7_f32);
+        let w = Complex::new(0.5_f64, -0.7_f64);
+        let result = z.asin().unwrap();
+        let expected = w.asin().unwrap();
+        assert_complex_close!(expected, Complex::new(result.re as f64, result.im as f64), 0.000001);
+    }
+}
@@ -0,0 +1,52 @@
+use std::fmt;
+
+use crate::complex::Complex;
+use crate::domain::Domain;
+
+/// The result of evaluating an expression in the shell, which is either a
+/// single number or, for functions such as `roots`, a vector of them.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Value<N = Complex> {
+    Number(N),
+    Vector(Vec<N>),
+}
+
+impl<N: Domain> Value<N> {
+    pub fn to_std_string(&self, magnitude: usize, precision: usize) -> String {
+        match self {
+            Value::Number(c) => c.to_std_string(magnitude, precision),
+            Value::Vector(v) => format!(
+                "[{}]",
+                v.iter()
+                    .map(|c| c.to_std_string(magnitude, precision))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
+        }
+    }
+}
+
+impl<N: Domain> fmt::Display for Value<N> {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        let magnitude = fmt.width().unwrap_or(12);
+        let precision = fmt.precision().unwrap_or(6);
+
+        write!(fmt, "{}", self.to_std_string(magnitude, precision))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    #[rstest(
+        v, expected,
+        case::number(Value::Number(Complex::new(1.0, -1.0)), "{1 - i}"),
+        case::empty_vector(Value::Vector(vec![]), "[]"),
+        case::vector(Value::Vector(vec![Complex::ONE, Complex::I]), "[{1}, {i}]")
+    )]
+    fn string(v: Value, expected: &str) {
+        assert_eq!(expected, v.to_string());
+    }
+}